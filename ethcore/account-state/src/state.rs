@@ -31,6 +31,7 @@ use std::{
 
 use common_types::{
 	state_diff::StateDiff,
+	state_override::StateOverride,
 	basic_account::BasicAccount,
 	errors::EthcoreError as Error,
 };
@@ -701,6 +702,26 @@ impl<B: Backend> State<B> {
 		Ok(())
 	}
 
+	/// Replace the code, balance, nonce and storage of accounts as described by `overrides`.
+	/// Meant for states used for simulations only; the changes are not valid transitions.
+	pub fn apply_override(&mut self, overrides: &StateOverride) -> TrieResult<()> {
+		for (address, account) in overrides {
+			if let Some(ref code) = account.code {
+				self.reset_code(address, code.clone())?;
+			}
+			if let Some(balance) = account.balance {
+				self.require(address, false)?.set_balance(balance);
+			}
+			if let Some(nonce) = account.nonce {
+				self.require(address, false)?.set_nonce(nonce);
+			}
+			for (key, value) in &account.storage {
+				self.set_storage(address, *key, *value)?;
+			}
+		}
+		Ok(())
+	}
+
 	fn touch(&mut self, a: &Address) -> TrieResult<()> {
 		self.require(a, false)?;
 		Ok(())
@@ -2361,6 +2361,32 @@ mod tests {
 		assert_eq!(blocks_ba, vec![3]);
 	}
 
+	#[test]
+	fn test_bloom_filter_multiple_blooms() {
+		let bloom_b1 = Bloom::from_str("00000020000000000000000000000000000000000000000002000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000400000000000000000000002000").unwrap();
+
+		let bloom_b2 = Bloom::from_str("00000000000000000000000000000000000000000000020000001000000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000008000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000000000000000000000000002000000000000000000040000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+
+		let bloom_ba = Bloom::from_str("00000000000000000000000000000000000000000000020000000800000000000000000000000000000000000000000000000000000000000000000000000000100000000000000000008000000000000000000000000000000000040000000000000000000000000000000000000000000000000000000000000000000000800000000000000000000000000000000000000000000000000000000000008000000000000000000000000000000000000000000000000000000000000000000000000000000000000002000000000000000000040000000000000000000000000000000000000000000000000000000000000000000000000000000000000000").unwrap();
+
+		let genesis = BlockBuilder::genesis();
+		let b1 = genesis.add_block_with_bloom(bloom_b1);
+		let b2 = b1.add_block_with_bloom(bloom_b2);
+		let b3 = b2.add_block_with_bloom(bloom_ba);
+
+		let db = new_db();
+		let bc = new_chain(genesis.last().encoded(), db.clone());
+		insert_block(&db, &bc, b1.last().encoded(), vec![]);
+		insert_block(&db, &bc, b2.last().encoded(), vec![]);
+		insert_block(&db, &bc, b3.last().encoded(), vec![]);
+
+		// blocks matching any of the blooms are returned in a single pass.
+		assert_eq!(bc.blocks_with_bloom(&[bloom_b1, bloom_b2], 0, 5), vec![1, 2]);
+		assert_eq!(bc.blocks_with_bloom(&[bloom_b1, bloom_ba], 0, 5), vec![1, 3]);
+		assert_eq!(bc.blocks_with_bloom(&[bloom_b1, bloom_b2, bloom_ba], 0, 5), vec![1, 2, 3]);
+		assert_eq!(bc.blocks_with_bloom(&[bloom_b1, bloom_b2, bloom_ba], 2, 2), vec![2]);
+	}
+
 	#[test]
 	fn test_insert_unordered() {
 		let bloom_b1 = Bloom::from_str("00000020000000000000000000000000000000000000000002000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000040000000000000010000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000008000400000000000000000000002000").unwrap();
@@ -39,10 +39,10 @@ use common_types::{
 	header::Header,
 	ids::{BlockId, TransactionId, TraceId, UncleId},
 	log_entry::LocalizedLogEntry,
-	pruning_info::PruningInfo,
+	pruning_info::{PruningInfo, StateAvailability},
 	receipt::LocalizedReceipt,
 	trace_filter::Filter as TraceFilter,
-	transaction::{self, Action, LocalizedTransaction, CallError, SignedTransaction, UnverifiedTransaction},
+	transaction::{self, Action, LocalizedTransaction, CallError, SignedTransaction, TransactionStatus, UnverifiedTransaction},
 	tree_route::TreeRoute,
 	verification::{VerificationQueueInfo, Unverified},
 };
@@ -162,6 +162,10 @@ pub trait EngineClient: Sync + Send + ChainInfo {
 	/// Submit a seal for a block in the mining queue.
 	fn submit_seal(&self, block_hash: H256, seal: Vec<Bytes>);
 
+	/// Submit a seal for a block in the mining queue and import the sealed block
+	/// into the chain in one step, returning the hash of the imported block.
+	fn submit_seal_and_import(&self, block_hash: H256, seal: Vec<Bytes>) -> EthcoreResult<H256>;
+
 	/// Broadcast a consensus message to the network.
 	fn broadcast_consensus_message(&self, message: Bytes);
 
@@ -276,6 +280,13 @@ pub trait BlockChainClient:
 			Therefore storage_at has returned Some; qed")
 	}
 
+	/// Check whether an account exists in the given block's state. Unlike `nonce`, `balance`
+	/// and `code`, this distinguishes an absent account from an empty one.
+	///
+	/// May not return None if given BlockId::Latest.
+	/// Returns None if and only if the block's root hash has been pruned from the DB.
+	fn account_exists(&self, address: &Address, id: BlockId) -> Option<bool>;
+
 	/// Get a list of all accounts in the block `id`, if fat DB is in operation, otherwise `None`.
 	/// If `after` is set the list starts with the following item.
 	fn list_accounts(&self, id: BlockId, after: Option<&Address>, count: u64) -> Option<Vec<Address>>;
@@ -287,9 +298,32 @@ pub trait BlockChainClient:
 	/// Get transaction with given hash.
 	fn transaction(&self, id: TransactionId) -> Option<LocalizedTransaction>;
 
+	/// Get the lifecycle state of a transaction, looking at both the transaction pool and the chain.
+	fn transaction_status(&self, hash: &H256) -> TransactionStatus;
+
 	/// Get uncle with given id.
 	fn uncle(&self, id: UncleId) -> Option<encoded::Header>;
 
+	/// Get the number of uncles in the given block.
+	fn uncles_count(&self, id: BlockId) -> Option<usize> {
+		self.block_body(id).map(|body| body.uncles_count())
+	}
+
+	/// Get a summary of the given block without decoding its transactions.
+	fn block_summary(&self, id: BlockId) -> Option<BlockSummary> {
+		let header = self.block_header(id)?;
+		let body = self.block_body(id)?;
+		Some(BlockSummary {
+			number: header.number(),
+			hash: header.hash(),
+			timestamp: header.timestamp(),
+			gas_used: header.gas_used(),
+			gas_limit: header.gas_limit(),
+			transaction_count: body.transactions_count(),
+			author: header.author(),
+		})
+	}
+
 	/// Get transaction receipt with given hash.
 	fn transaction_receipt(&self, id: TransactionId) -> Option<LocalizedReceipt>;
 
@@ -338,8 +372,9 @@ pub trait BlockChainClient:
 	/// Returns traces created by transaction from block.
 	fn block_traces(&self, trace: BlockId) -> Option<Vec<LocalizedTrace>>;
 
-	/// Get last hashes starting from best block.
-	fn last_hashes(&self) -> LastHashes;
+	/// Get the hashes of the 256 blocks up to and including `parent`, most recent first,
+	/// as seen by a block built on `parent`. Consecutive calls share a cached window.
+	fn last_hashes(&self, parent: &H256) -> Arc<LastHashes>;
 
 	/// List all ready transactions that should be propagated to other peers.
 	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>>;
@@ -367,6 +402,20 @@ pub trait BlockChainClient:
 		corpus.into()
 	}
 
+	/// Gas prices at the given percentiles of the transactions from at least the last
+	/// `sample_blocks` blocks. Empty blocks don't count towards the sample and fewer
+	/// blocks are used if the chain is shorter. Returns an empty vector if no
+	/// transactions were found.
+	fn gas_price_percentiles(&self, sample_blocks: usize, percentiles: &[u8]) -> Vec<U256> {
+		let corpus = self.gas_price_corpus(sample_blocks);
+		if corpus.is_empty() {
+			return Vec::new();
+		}
+		percentiles.iter()
+			.map(|p| *corpus.percentile(*p as usize).unwrap_or(&corpus[0]))
+			.collect()
+	}
+
 	/// Get the preferred chain ID to sign on
 	fn signing_chain_id(&self) -> Option<u64>;
 
@@ -395,12 +444,46 @@ pub trait BlockChainClient:
 	/// Returns information about pruning/data availability.
 	fn pruning_info(&self) -> PruningInfo;
 
+	/// Returns whether the state at the given block is available, has been pruned
+	/// or belongs to a block the client doesn't know about.
+	fn state_availability(&self, id: BlockId) -> StateAvailability {
+		match BlockChainClient::block_number(self, id) {
+			Some(number) if number >= self.pruning_info().earliest_state => StateAvailability::Available,
+			Some(_) => StateAvailability::Pruned,
+			None => StateAvailability::Unknown,
+		}
+	}
+
+	/// Returns the number of most recent blocks for which full block data is retained,
+	/// or `None` if the whole chain is available (e.g. on an archive node).
+	fn history_size(&self) -> Option<u64> {
+		None
+	}
+
+	/// Returns the number of the oldest block whose full data is still retained,
+	/// or `None` if the whole chain is available. See `history_size`.
+	fn history_horizon(&self) -> Option<BlockNumber> {
+		self.history_size().map(|size| self.chain_info().best_block_number.saturating_sub(size) + 1)
+	}
+
+	/// Returns an iterator over the raw RLP of canonical blocks numbered `from` to `to`
+	/// (inclusive), in ascending order. Missing blocks are skipped.
+	fn block_range(&self, from: BlockNumber, to: BlockNumber) -> Box<dyn Iterator<Item = (BlockNumber, Bytes)> + '_> {
+		Box::new((from..=to).filter_map(move |number| {
+			self.block(BlockId::Number(number)).map(|block| (number, block.into_inner()))
+		}))
+	}
+
 	/// Returns a transaction signed with the key configured in the engine signer.
 	fn create_transaction(&self, tx_request: TransactionRequest) -> Result<SignedTransaction, transaction::Error>;
 
 	/// Schedule state-altering transaction to be executed on the next pending
 	/// block with the given gas and nonce parameters.
 	fn transact(&self, tx_request: TransactionRequest) -> Result<(), transaction::Error>;
+
+	/// Import an already signed transaction into the queue as a local transaction.
+	/// Returns the transaction hash on success.
+	fn submit_transaction(&self, signed: SignedTransaction) -> Result<H256, transaction::Error>;
 }
 
 /// The data required for a `Client` to create a transaction.
@@ -457,6 +540,25 @@ impl TransactionRequest {
 	}
 }
 
+/// Summary of a block, assembled from its header and body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockSummary {
+	/// Block number.
+	pub number: BlockNumber,
+	/// Block hash.
+	pub hash: H256,
+	/// Block timestamp.
+	pub timestamp: u64,
+	/// Gas used by the block's transactions.
+	pub gas_used: U256,
+	/// Block gas limit.
+	pub gas_limit: U256,
+	/// Number of transactions in the block.
+	pub transaction_count: usize,
+	/// Block author.
+	pub author: Address,
+}
+
 /// resets the blockchain
 pub trait BlockChainReset {
 	/// reset to best_block - n
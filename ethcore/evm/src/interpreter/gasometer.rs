@@ -317,16 +317,7 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 	}
 
 	fn mem_gas_cost(&self, schedule: &Schedule, current_mem_size: usize, mem_size: &Gas) -> vm::Result<(Gas, Gas, usize)> {
-		let gas_for_mem = |mem_size: Gas| {
-			let s = mem_size >> 5;
-			// s * memory_gas + s * s / quad_coeff_div
-			let a = overflowing!(s.overflow_mul(Gas::from(schedule.memory_gas)));
-
-			// Calculate s*s/quad_coeff_div
-			assert_eq!(schedule.quad_coeff_div, 512);
-			let b = overflowing!(s.overflow_mul_shr(s, 9));
-			Ok(overflowing!(a.overflow_add(b)))
-		};
+		let gas_for_mem = |mem_size: Gas| mem_words_gas(schedule, mem_size >> 5);
 
 		let current_mem_size = Gas::from(current_mem_size);
 		let req_mem_size_rounded = overflowing!(to_word_size(*mem_size)) << 5;
@@ -342,6 +333,31 @@ impl<Gas: evm::CostType> Gasometer<Gas> {
 	}
 }
 
+/// Gas charged for the memory expansion from `current_words` to `new_words` 32-byte words,
+/// i.e. the growth of `memory_gas * w + w * w / quad_coeff_div`. Zero if memory doesn't grow.
+pub fn mem_gas_cost(schedule: &Schedule, current_words: usize, new_words: usize) -> U256 {
+	if new_words <= current_words {
+		return U256::zero();
+	}
+
+	let gas_for = |words: usize| mem_words_gas(schedule, U256::from(words))
+		.expect("usize word counts cannot overflow U256 memory gas; qed");
+	gas_for(new_words) - gas_for(current_words)
+}
+
+/// Total gas for `words` words of memory: `memory_gas * words + words * words / quad_coeff_div`.
+fn mem_words_gas<Gas: evm::CostType>(schedule: &Schedule, words: Gas) -> vm::Result<Gas> {
+	let a = overflowing!(words.overflow_mul(Gas::from(schedule.memory_gas)));
+
+	// Calculate s*s/quad_coeff_div, shifting when the divisor allows it to avoid intermediate overflow.
+	let b = if schedule.quad_coeff_div.is_power_of_two() {
+		overflowing!(words.overflow_mul_shr(words, schedule.quad_coeff_div.trailing_zeros() as usize))
+	} else {
+		overflowing!(words.overflow_mul(words)) / Gas::from(schedule.quad_coeff_div)
+	};
+	Ok(overflowing!(a.overflow_add(b)))
+}
+
 #[inline]
 fn mem_needed_const<Gas: evm::CostType>(mem: &U256, add: usize) -> vm::Result<Gas> {
 	Gas::from_u256(overflowing!(mem.overflowing_add(U256::from(add))))
@@ -505,3 +521,21 @@ fn test_calculate_mem_cost() {
 	assert_eq!(new_mem_gas, 3);
 	assert_eq!(mem_size, 32);
 }
+
+#[test]
+fn test_mem_gas_cost_reference_values() {
+	let schedule = Schedule::default();
+
+	// no growth
+	assert_eq!(mem_gas_cost(&schedule, 0, 0), U256::zero());
+	assert_eq!(mem_gas_cost(&schedule, 10, 4), U256::zero());
+	// 3 * w + w * w / 512
+	assert_eq!(mem_gas_cost(&schedule, 0, 1), U256::from(3));
+	assert_eq!(mem_gas_cost(&schedule, 0, 32), U256::from(98));
+	assert_eq!(mem_gas_cost(&schedule, 0, 1024), U256::from(5120));
+	assert_eq!(mem_gas_cost(&schedule, 32, 1024), U256::from(5022));
+
+	let mut schedule = Schedule::default();
+	schedule.quad_coeff_div = 100;
+	assert_eq!(mem_gas_cost(&schedule, 0, 1024), U256::from(3072 + 10485));
+}
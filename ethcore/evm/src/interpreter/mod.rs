@@ -44,6 +44,7 @@ use instructions::{self, Instruction, InstructionInfo};
 use self::gasometer::Gasometer;
 use self::stack::{Stack, VecStack};
 use self::memory::Memory;
+pub use self::gasometer::mem_gas_cost;
 pub use self::shared_cache::SharedCache;
 
 use bit_set::BitSet;
@@ -411,6 +412,10 @@ impl<Cost: CostType> Interpreter<Cost> {
 					Ok(x) => x,
 					Err(e) => return InterpreterResult::Done(Err(e))
 				};
+				// every loop has to jump back, so this is where long-running code is stopped.
+				if ext.deadline_exceeded() {
+					return InterpreterResult::Done(Err(vm::Error::Timeout));
+				}
 				self.reader.position = pos;
 			},
 			InstructionResult::StopExecutionNeedsReturn {gas, init_off, init_size, apply} => {
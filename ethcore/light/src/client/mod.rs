@@ -629,6 +629,11 @@ impl<T: ChainDataFetcher> client_traits::ChainInfo for Client<T> {
 impl<T: ChainDataFetcher> client_traits::EngineClient for Client<T> {
 	fn update_sealing(&self, _force: ForceUpdateSealing) {}
 	fn submit_seal(&self, _block_hash: H256, _seal: Vec<Vec<u8>>) { }
+
+	fn submit_seal_and_import(&self, _block_hash: H256, _seal: Vec<Vec<u8>>) -> EthcoreResult<H256> {
+		// light clients never hand out sealing work, so no hash can match.
+		Err(Error::PowHashInvalid)
+	}
 	fn broadcast_consensus_message(&self, _message: Vec<u8>) { }
 
 	fn epoch_transition_for(&self, parent_hash: H256) -> Option<EpochTransition> {
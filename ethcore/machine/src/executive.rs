@@ -16,7 +16,7 @@
 
 //! Transaction Execution environment.
 
-use std::{cmp, convert::TryFrom, sync::Arc};
+use std::{cmp, convert::TryFrom, sync::Arc, time::Instant};
 
 use crossbeam_utils::thread;
 use ethereum_types::{H256, U256, U512, Address};
@@ -36,14 +36,14 @@ use trace::{self, Tracer, VMTracer};
 use common_types::{
 	errors::ExecutionError,
 	transaction::{Action, SignedTransaction},
-	engines::machine::Executed,
+	engines::machine::{Executed, FailedFrame},
 };
 
 use crate::{
 	Machine,
 	substate::Substate,
 	externalities::{Externalities, OutputPolicy, OriginInfo},
-	transaction_ext::Transaction,
+	transaction_ext::intrinsic_gas,
 };
 
 #[cfg(debug_assertions)]
@@ -133,6 +133,8 @@ pub struct TransactOptions<T, V> {
 	pub check_nonce: bool,
 	/// Records the output from init contract calls.
 	pub output_from_init_contract: bool,
+	/// Abort execution once this instant has passed.
+	pub deadline: Option<Instant>,
 }
 
 impl<T, V> TransactOptions<T, V> {
@@ -143,6 +145,7 @@ impl<T, V> TransactOptions<T, V> {
 			vm_tracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			deadline: None,
 		}
 	}
 
@@ -157,6 +160,12 @@ impl<T, V> TransactOptions<T, V> {
 		self.output_from_init_contract = true;
 		self
 	}
+
+	/// Aborts execution with `vm::Error::Timeout` once `deadline` has passed.
+	pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+		self.deadline = deadline;
+		self
+	}
 }
 
 impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
@@ -167,6 +176,7 @@ impl TransactOptions<trace::ExecutiveTracer, trace::ExecutiveVMTracer> {
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
 			output_from_init_contract: false,
+			deadline: None,
 		}
 	}
 }
@@ -179,6 +189,7 @@ impl TransactOptions<trace::ExecutiveTracer, trace::NoopVMTracer> {
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			deadline: None,
 		}
 	}
 }
@@ -191,6 +202,7 @@ impl TransactOptions<trace::NoopTracer, trace::ExecutiveVMTracer> {
 			vm_tracer: trace::ExecutiveVMTracer::toplevel(),
 			check_nonce: true,
 			output_from_init_contract: false,
+			deadline: None,
 		}
 	}
 }
@@ -203,6 +215,7 @@ impl TransactOptions<trace::NoopTracer, trace::NoopVMTracer> {
 			vm_tracer: trace::NoopVMTracer,
 			check_nonce: true,
 			output_from_init_contract: false,
+			deadline: None,
 		}
 	}
 }
@@ -231,8 +244,10 @@ pub struct CallCreateExecutive<'a> {
 	stack_depth: usize,
 	static_flag: bool,
 	is_create: bool,
+	address: Address,
 	gas: U256,
 	kind: CallCreateExecutiveKind,
+	deadline: Option<Instant>,
 }
 
 impl<'a> CallCreateExecutive<'a> {
@@ -241,6 +256,7 @@ impl<'a> CallCreateExecutive<'a> {
 		trace!("Executive::call(params={:?}) self.env_info={:?}, parent_static={}", params, info, parent_static_flag);
 
 		let gas = params.gas;
+		let address = params.address;
 		let static_flag = parent_static_flag || params.action_type == ActionType::StaticCall;
 
 		// if destination is builtin, try to execute it
@@ -261,8 +277,9 @@ impl<'a> CallCreateExecutive<'a> {
 		};
 
 		Self {
-			info, machine, schedule, factory, depth, stack_depth, static_flag, kind, gas,
+			info, machine, schedule, factory, depth, stack_depth, static_flag, kind, gas, address,
 			is_create: false,
+			deadline: None,
 		}
 	}
 
@@ -271,15 +288,23 @@ impl<'a> CallCreateExecutive<'a> {
 		trace!("Executive::create(params={:?}) self.env_info={:?}, static={}", params, info, static_flag);
 
 		let gas = params.gas;
+		let address = params.address;
 
 		let kind = CallCreateExecutiveKind::ExecCreate(params, Substate::new());
 
 		Self {
-			info, machine, schedule, factory, depth, stack_depth, static_flag, kind, gas,
+			info, machine, schedule, factory, depth, stack_depth, static_flag, kind, gas, address,
 			is_create: true,
+			deadline: None,
 		}
 	}
 
+	/// Aborts execution of this executive and all its sub-calls once `deadline` has passed.
+	pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+		self.deadline = deadline;
+		self
+	}
+
 	/// If this executive contains an unconfirmed substate, returns a mutable reference to it.
 	pub fn unconfirmed_substate(&mut self) -> Option<&mut Substate> {
 		match self.kind {
@@ -349,6 +374,7 @@ impl<'a> CallCreateExecutive<'a> {
 				| Err(vm::Error::MutableCallInStaticContext)
 				| Err(vm::Error::OutOfBounds)
 				| Err(vm::Error::Reverted)
+				| Err(vm::Error::Timeout)
 				| Ok(FinalizationResult { apply_state: false, .. }) => {
 					state.revert_to_checkpoint();
 			},
@@ -359,6 +385,20 @@ impl<'a> CallCreateExecutive<'a> {
 		}
 	}
 
+	/// Remembers the frame at `address` and `depth` if it failed and is nested deeper than the
+	/// failure recorded so far. Of several failures at the same depth the earliest one is kept.
+	fn record_failure(failure: &mut Option<FailedFrame>, address: Address, depth: usize, result: &vm::Result<FinalizationResult>) {
+		let error = match *result {
+			Ok(FinalizationResult { apply_state: true, .. }) => return,
+			Ok(_) => vm::Error::Reverted,
+			Err(ref err) => err.clone(),
+		};
+
+		if failure.as_ref().map_or(true, |failure| depth > failure.depth) {
+			*failure = Some(FailedFrame { address, depth, error });
+		}
+	}
+
 	/// Creates `Externalities` from `Executive`.
 	fn as_externalities<'any, B: 'any + StateBackend, T, V>(
 		state: &'any mut State<B>,
@@ -470,7 +510,8 @@ impl<'a> CallCreateExecutive<'a> {
 
 				let out = match exec {
 					Some(exec) => {
-						let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, OutputPolicy::Return, tracer, vm_tracer);
+						let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, OutputPolicy::Return, tracer, vm_tracer)
+							.with_deadline(self.deadline);
 						match exec.exec(&mut ext) {
 							Ok(val) => Ok(val.finalize(ext)),
 							Err(err) => Err(err),
@@ -521,7 +562,8 @@ impl<'a> CallCreateExecutive<'a> {
 
 				let out = match exec {
 					Some(exec) => {
-						let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, OutputPolicy::InitContract, tracer, vm_tracer);
+						let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, OutputPolicy::InitContract, tracer, vm_tracer)
+							.with_deadline(self.deadline);
 						match exec.exec(&mut ext) {
 							Ok(val) => Ok(val.finalize(ext)),
 							Err(err) => Err(err),
@@ -558,7 +600,8 @@ impl<'a> CallCreateExecutive<'a> {
 				let out = {
 					let exec = resume.resume_call(result);
 
-					let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, if self.is_create { OutputPolicy::InitContract } else { OutputPolicy::Return }, tracer, vm_tracer);
+					let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, if self.is_create { OutputPolicy::InitContract } else { OutputPolicy::Return }, tracer, vm_tracer)
+						.with_deadline(self.deadline);
 					match exec.exec(&mut ext) {
 						Ok(val) => Ok(val.finalize(ext)),
 						Err(err) => Err(err),
@@ -597,7 +640,8 @@ impl<'a> CallCreateExecutive<'a> {
 				let out = {
 					let exec = resume.resume_create(result);
 
-					let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, if self.is_create { OutputPolicy::InitContract } else { OutputPolicy::Return }, tracer, vm_tracer);
+					let mut ext = Self::as_externalities(state, self.info, self.machine, self.schedule, self.depth, self.stack_depth, self.static_flag, &origin_info, &mut unconfirmed_substate, if self.is_create { OutputPolicy::InitContract } else { OutputPolicy::Return }, tracer, vm_tracer)
+						.with_deadline(self.deadline);
 					match exec.exec(&mut ext) {
 						Ok(val) => Ok(val.finalize(ext)),
 						Err(err) => Err(err),
@@ -628,8 +672,11 @@ impl<'a> CallCreateExecutive<'a> {
 	}
 
 	/// Execute and consume the current executive. This function handles resume traps and sub-level tracing. The caller is expected to handle current-level tracing.
-	pub fn consume<B: 'a + StateBackend, T: Tracer, V: VMTracer>(self, state: &mut State<B>, top_substate: &mut Substate, tracer: &mut T, vm_tracer: &mut V) -> vm::Result<FinalizationResult> {
-		let mut last_res = Some((false, self.gas, self.exec(state, top_substate, tracer, vm_tracer)));
+	///
+	/// The most deeply nested frame that fails, this one included, is recorded in `failure` unless
+	/// it already holds a deeper one.
+	pub fn consume<B: 'a + StateBackend, T: Tracer, V: VMTracer>(self, state: &mut State<B>, top_substate: &mut Substate, tracer: &mut T, vm_tracer: &mut V, failure: &mut Option<FailedFrame>) -> vm::Result<FinalizationResult> {
+		let mut last_res = Some((false, self.gas, (self.address, self.depth), self.exec(state, top_substate, tracer, vm_tracer)));
 
 		let mut callstack: Vec<(Option<Address>, CallCreateExecutive<'a>)> = Vec::new();
 		loop {
@@ -643,12 +690,14 @@ impl<'a> CallCreateExecutive<'a> {
 								None => top_substate,
 							};
 
-							last_res = Some((exec.is_create, exec.gas, exec.exec(state, parent_substate, tracer, vm_tracer)));
+							last_res = Some((exec.is_create, exec.gas, (exec.address, exec.depth), exec.exec(state, parent_substate, tracer, vm_tracer)));
 						},
 						None => panic!("When callstack only had one item and it was executed, this function would return; callstack never reaches zero item; qed"),
 					}
 				},
-				Some((is_create, gas, Ok(val))) => {
+				Some((is_create, gas, (address, depth), Ok(val))) => {
+					Self::record_failure(failure, address, depth, &val);
+
 					let current = callstack.pop();
 
 					match current {
@@ -681,7 +730,7 @@ impl<'a> CallCreateExecutive<'a> {
 								};
 
 								let contract_create_result = into_contract_create_result(val, &address, exec.unconfirmed_substate().expect("Executive is resumed from a create; it has an unconfirmed substate; qed"));
-								last_res = Some((exec.is_create, exec.gas, exec.resume_create(
+								last_res = Some((exec.is_create, exec.gas, (exec.address, exec.depth), exec.resume_create(
 									contract_create_result,
 									state,
 									parent_substate,
@@ -712,7 +761,7 @@ impl<'a> CallCreateExecutive<'a> {
 									None => top_substate,
 								};
 
-								last_res = Some((exec.is_create, exec.gas, exec.resume_call(
+								last_res = Some((exec.is_create, exec.gas, (exec.address, exec.depth), exec.resume_call(
 									into_message_call_result(val),
 									state,
 									parent_substate,
@@ -724,7 +773,7 @@ impl<'a> CallCreateExecutive<'a> {
 						None => return val,
 					}
 				},
-				Some((_, _, Err(TrapError::Call(subparams, resume)))) => {
+				Some((_, _, _, Err(TrapError::Call(subparams, resume)))) => {
 					tracer.prepare_trace_call(&subparams, resume.depth + 1, resume.machine.builtin(&subparams.address, resume.info.number).is_some());
 					vm_tracer.prepare_subtrace(subparams.code.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 
@@ -737,13 +786,13 @@ impl<'a> CallCreateExecutive<'a> {
 						resume.depth + 1,
 						resume.stack_depth,
 						resume.static_flag,
-					);
+					).with_deadline(resume.deadline);
 
 					callstack.push((None, resume));
 					callstack.push((None, sub_exec));
 					last_res = None;
 				},
-				Some((_, _, Err(TrapError::Create(subparams, address, resume)))) => {
+				Some((_, _, _, Err(TrapError::Create(subparams, address, resume)))) => {
 					tracer.prepare_trace_create(&subparams);
 					vm_tracer.prepare_subtrace(subparams.code.as_ref().map_or_else(|| &[] as &[u8], |d| &*d as &[u8]));
 
@@ -756,7 +805,7 @@ impl<'a> CallCreateExecutive<'a> {
 						resume.depth + 1,
 						resume.stack_depth,
 						resume.static_flag
-					);
+					).with_deadline(resume.deadline);
 
 					callstack.push((Some(address), resume));
 					callstack.push((None, sub_exec));
@@ -775,6 +824,9 @@ pub struct Executive<'a, B: 'a> {
 	schedule: &'a Schedule,
 	depth: usize,
 	static_flag: bool,
+	deadline: Option<Instant>,
+	fee_recipient: Option<Address>,
+	deepest_failure: Option<FailedFrame>,
 }
 
 impl<'a, B: 'a + StateBackend> Executive<'a, B> {
@@ -787,6 +839,9 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			schedule: schedule,
 			depth: 0,
 			static_flag: false,
+			deadline: None,
+			fee_recipient: None,
+			deepest_failure: None,
 		}
 	}
 
@@ -799,13 +854,29 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			schedule: schedule,
 			depth: parent_depth + 1,
 			static_flag: static_flag,
+			deadline: None,
+			fee_recipient: None,
+			deepest_failure: None,
 		}
 	}
 
+	/// Aborts any execution started from this executive once `deadline` has passed.
+	pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+		self.deadline = deadline;
+		self
+	}
+
+	/// Credits transaction fees to `fee_recipient` instead of the block author, if set.
+	pub fn with_fee_recipient(mut self, fee_recipient: Option<Address>) -> Self {
+		self.fee_recipient = fee_recipient;
+		self
+	}
+
 	/// This function should be used to execute transaction.
 	pub fn transact<T, V>(&'a mut self, t: &SignedTransaction, options: TransactOptions<T, V>)
 		-> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer,
 	{
+		self.deadline = options.deadline.or(self.deadline);
 		self.transact_with_tracer(
 			t,
 			options.check_nonce,
@@ -832,6 +903,31 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		self.transact(t, options)
 	}
 
+	/// Execute a transaction like `transact_virtual`, but revert all of its state changes,
+	/// including the sender's nonce and balance, before returning.
+	/// Used for simulating a transaction against the current state.
+	pub fn transact_dry_run<T, V>(&mut self, t: &SignedTransaction, options: TransactOptions<T, V>)
+		-> Result<Executed<T::Output, V::Output>, ExecutionError> where T: Tracer, V: VMTracer,
+	{
+		self.state.checkpoint();
+		let result = {
+			let mut ex = Executive {
+				state: &mut *self.state,
+				info: self.info,
+				machine: self.machine,
+				schedule: self.schedule,
+				depth: self.depth,
+				static_flag: self.static_flag,
+				deadline: self.deadline,
+				fee_recipient: self.fee_recipient,
+				deepest_failure: None,
+			};
+			ex.transact_virtual(t, options)
+		};
+		self.state.revert_to_checkpoint();
+		result
+	}
+
 	/// Execute transaction/call with tracing enabled
 	fn transact_with_tracer<T, V>(
 		&'a mut self,
@@ -845,7 +941,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let nonce = self.state.nonce(&sender)?;
 
 		let schedule = self.schedule;
-		let base_gas_required = U256::from(t.gas_required(&schedule));
+		let base_gas_required = intrinsic_gas(t, &schedule);
 
 		if t.gas < base_gas_required {
 			return Err(ExecutionError::NotEnoughBaseGas { required: base_gas_required, got: t.gas });
@@ -894,6 +990,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			&mut cleanup_mode(&mut substate, &schedule)
 		)?;
 
+		self.deepest_failure = None;
 		let (result, output) = match t.action {
 			Action::Create => {
 				let (new_address, code_hash) = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &nonce, &t.data);
@@ -945,7 +1042,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		};
 
 		// finalize here!
-		Ok(self.finalize(t, substate, result, output, tracer.drain(), vm_tracer.drain())?)
+		Ok(self.finalize(t, base_gas_required, substate, result, output, tracer.drain(), vm_tracer.drain())?)
 	}
 
 	/// Calls contract function with given contract params and stack depth.
@@ -975,7 +1072,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			self.depth,
 			stack_depth,
 			self.static_flag
-		).consume(self.state, substate, tracer, vm_tracer);
+		).with_deadline(self.deadline).consume(self.state, substate, tracer, vm_tracer, &mut self.deepest_failure);
 
 		match result {
 			Ok(ref val) if val.apply_state => {
@@ -1065,7 +1162,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 			self.depth,
 			stack_depth,
 			self.static_flag
-		).consume(self.state, substate, tracer, vm_tracer);
+		).with_deadline(self.deadline).consume(self.state, substate, tracer, vm_tracer, &mut self.deepest_failure);
 
 		match result {
 			Ok(ref val) if val.apply_state => {
@@ -1130,9 +1227,13 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 	}
 
 	/// Finalizes the transaction (does refunds and suicides).
+	///
+	/// `base_gas` is the intrinsic gas charged before execution; the VM only ever
+	/// sees the remaining `t.gas - base_gas`.
 	fn finalize<T, V>(
 		&mut self,
 		t: &SignedTransaction,
+		base_gas: U256,
 		mut substate: Substate,
 		result: vm::Result<FinalizationResult>,
 		output: Bytes,
@@ -1148,12 +1249,13 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		let suicide_refunds = U256::from(schedule.suicide_refund_gas) * U256::from(substate.suicides.len());
 		let refunds_bound = sstore_refunds + suicide_refunds;
 
-		// real amount to refund
+		// real amount to refund, capped at half of the gas used including the base gas
+		let init_gas = t.gas - base_gas;
 		let gas_left_prerefund = match result { Ok(FinalizationResult{ gas_left, .. }) => gas_left, _ => 0.into() };
-		let refunded = cmp::min(refunds_bound, (t.gas - gas_left_prerefund) >> 1);
-		let gas_left = gas_left_prerefund + refunded;
-
-		let gas_used = t.gas.saturating_sub(gas_left);
+		let gas_used_prerefund = base_gas + (init_gas - gas_left_prerefund);
+		let refunded = cmp::min(refunds_bound, gas_used_prerefund >> 1);
+		let gas_used = gas_used_prerefund - refunded;
+		let gas_left = t.gas - gas_used;
 		let (refund_value, overflow_1) = gas_left.overflowing_mul(t.gas_price);
 		let (fees_value, overflow_2) = gas_used.overflowing_mul(t.gas_price);
 		if overflow_1 || overflow_2 {
@@ -1168,8 +1270,9 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 		trace!(target: "executive", "exec::finalize: Refunding refund_value={}, sender={}\n", refund_value, sender);
 		// Below: NoEmpty is safe since the sender must already be non-null to have sent this transaction
 		self.state.add_balance(&sender, &refund_value, CleanupMode::NoEmpty)?;
-		trace!(target: "executive", "exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &self.info.author);
-		self.state.add_balance(&self.info.author, &fees_value, cleanup_mode(&mut substate, &schedule))?;
+		let author = self.fee_recipient.unwrap_or(self.info.author);
+		trace!(target: "executive", "exec::finalize: Compensating author: fees_value={}, author={}\n", fees_value, &author);
+		self.state.add_balance(&author, &fees_value, cleanup_mode(&mut substate, &schedule))?;
 
 		// perform suicides
 		for address in &substate.suicides {
@@ -1195,6 +1298,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					deepest_failure: self.deepest_failure.take(),
 				})
 			},
 			Ok(r) => {
@@ -1210,6 +1314,7 @@ impl<'a, B: 'a + StateBackend> Executive<'a, B> {
 					trace: trace,
 					vm_trace: vm_trace,
 					state_diff: None,
+					deepest_failure: self.deepest_failure.take(),
 				})
 			},
 		}
@@ -1231,6 +1336,7 @@ mod tests {
 	use account_state::CleanupMode;
 	use common_types::{
 		errors::ExecutionError,
+		engines::machine::FailedFrame,
 		transaction::{Action, Transaction},
 	};
 	use parity_crypto::publickey::{Generator, Random};
@@ -1239,7 +1345,8 @@ mod tests {
 	use vm::{ActionParams, ActionValue, EnvInfo, CreateContractAddress};
 	use ::trace::{
 		trace,
-		FlatTrace, Tracer, NoopTracer, ExecutiveTracer,
+		flat::{deepest_failure, DeepestFailure},
+		FlatTrace, TraceError, Tracer, NoopTracer, ExecutiveTracer,
 		VMTrace, VMOperation, VMExecutedOperation, MemoryDiff, StorageDiff, VMTracer, NoopVMTracer, ExecutiveVMTracer,
 	};
 
@@ -1459,6 +1566,103 @@ mod tests {
 		}]);
 	}
 
+	#[test]
+	fn test_deepest_failure_reports_inner_call() {
+		// A: call B with 10000 gas, then stop
+		// 60 00 60 00 60 00 60 00 60 00 - ret, args and value all zero
+		// 73 <B> - push B
+		// 61 2710 - push 10000
+		// f1 00 - call, stop
+		let a = Address::from_low_u64_be(0xa);
+		let b = Address::from_low_u64_be(0xb);
+		let code_a = "6000600060006000600073000000000000000000000000000000000000000b612710f100".from_hex().unwrap();
+		// B: 5b 60 00 56 - loop until out of gas
+		let code_b = "5b600056".from_hex().unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(a),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state();
+		state.init_code(&a, code_a).unwrap();
+		state.init_code(&b, code_b).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_byzantium_machine(5);
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			ex.transact(&t, TransactOptions::with_tracing()).unwrap()
+		};
+
+		// the outer call survives the failure of the inner one
+		assert!(executed.exception.is_none());
+		assert_eq!(deepest_failure(&executed.trace), Some(DeepestFailure {
+			address: Some(b),
+			depth: 1,
+			error: TraceError::OutOfGas,
+		}));
+		assert_eq!(executed.deepest_failure, Some(FailedFrame {
+			address: b,
+			depth: 1,
+			error: vm::Error::OutOfGas,
+		}));
+	}
+
+	#[test]
+	fn test_deepest_failure_without_tracing() {
+		// A: call B with 10000 gas, then revert
+		// 60 00 60 00 60 00 60 00 60 00 - ret, args and value all zero
+		// 73 <B> - push B
+		// 61 2710 - push 10000
+		// f1 - call
+		// 60 00 80 fd - revert(0, 0)
+		let a = Address::from_low_u64_be(0xa);
+		let b = Address::from_low_u64_be(0xb);
+		let code_a = "6000600060006000600073000000000000000000000000000000000000000b612710f1600080fd".from_hex().unwrap();
+		// B: 5b 60 00 56 - loop until out of gas
+		let code_b = "5b600056".from_hex().unwrap();
+
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(a),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state();
+		state.init_code(&a, code_a).unwrap();
+		state.init_code(&b, code_b).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_byzantium_machine(5);
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		// the outer revert is shallower, so the inner failure is the one reported
+		assert_eq!(executed.exception, Some(vm::Error::Reverted));
+		assert!(executed.trace.is_empty());
+		assert_eq!(executed.deepest_failure, Some(FailedFrame {
+			address: b,
+			depth: 1,
+			error: vm::Error::OutOfGas,
+		}));
+	}
+
 	#[test]
 	// Tracing is not suported in JIT
 	fn test_call_to_create() {
@@ -2000,6 +2204,111 @@ mod tests {
 		assert_eq!(state.storage_at(&contract, &H256::zero()).unwrap(), BigEndianHash::from_uint(&U256::from(1)));
 	}
 
+	evm_test!{test_transact_refund_includes_base_gas: test_transact_refund_includes_base_gas_int}
+	fn test_transact_refund_includes_base_gas(factory: Factory) {
+		// 60 00 60 00 55 00 - clear storage slot 0
+		let code = "600060005500".from_hex().unwrap();
+		let contract = Address::from_low_u64_be(0xc0de);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![0xff; 100],
+			gas: U256::from(100_000),
+			gas_price: U256::one(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(100_000), CleanupMode::NoEmpty).unwrap();
+		state.init_code(&contract, code).unwrap();
+		state.set_storage(&contract, H256::zero(), BigEndianHash::from_uint(&U256::one())).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		// base gas: 21000 + 100 * 68 = 27800, execution: 3 + 3 + 5000 = 5006.
+		// the 15000 sstore refund is below half of the 32806 used, so it is paid in full.
+		assert!(executed.exception.is_none());
+		assert_eq!(executed.gas_used, U256::from(32_806 - 15_000));
+		assert_eq!(state.balance(&sender).unwrap(), U256::from(100_000 - 17_806));
+		assert_eq!(state.storage_at(&contract, &H256::zero()).unwrap(), H256::zero());
+	}
+
+	evm_test!{test_transact_removes_touched_empty_account: test_transact_removes_touched_empty_account_int}
+	fn test_transact_removes_touched_empty_account(factory: Factory) {
+		let empty = Address::from_low_u64_be(0xe0);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(empty),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(21_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+
+		// EIP-161 state clearing is only active from Spurious Dragon on.
+		for (machine, removed) in vec![(make_frontier_machine(0), false), (make_byzantium_machine(0), true)] {
+			let mut state = get_temp_state_with_factory(factory.clone());
+			state.add_balance(&sender, &U256::from(1), CleanupMode::NoEmpty).unwrap();
+			state.add_balance(&empty, &U256::zero(), CleanupMode::ForceCreate).unwrap();
+			assert!(state.exists(&empty).unwrap());
+			let schedule = machine.schedule(info.number);
+
+			let executed = {
+				let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+				ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+			};
+
+			assert!(executed.exception.is_none());
+			assert_eq!(state.exists(&empty).unwrap(), !removed);
+			assert!(state.exists(&sender).unwrap());
+		}
+	}
+
+	evm_test!{test_transact_fee_recipient: test_transact_fee_recipient_int}
+	fn test_transact_fee_recipient(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(Address::from_low_u64_be(0xc0de)),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(21_000),
+			gas_price: U256::from(2),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+		let recipient = Address::from_low_u64_be(0xfee);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.add_balance(&sender, &U256::from(100_000), CleanupMode::NoEmpty).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		info.author = Address::from_low_u64_be(0xa0);
+		let machine = make_frontier_machine(0);
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule).with_fee_recipient(Some(recipient));
+			ex.transact(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert_eq!(executed.gas_used, U256::from(21_000));
+		assert_eq!(state.balance(&recipient).unwrap(), U256::from(42_000));
+		assert_eq!(state.balance(&info.author).unwrap(), U256::zero());
+	}
+
 	evm_test!{test_transact_invalid_nonce: test_transact_invalid_nonce_int}
 	fn test_transact_invalid_nonce(factory: Factory) {
 		let keypair = Random.generate().unwrap();
@@ -2101,6 +2410,125 @@ mod tests {
 		}
 	}
 
+	evm_test!{test_transact_deadline: test_transact_deadline_int}
+	fn test_transact_deadline(factory: Factory) {
+		use std::time::{Duration, Instant};
+
+		// JUMPDEST PUSH1 0x00 JUMP, which loops until the gas runs out.
+		let code = "5b600056".from_hex().unwrap();
+		let contract = Address::from_low_u64_be(0x1234);
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(contract),
+			value: U256::zero(),
+			data: vec![],
+			gas: U256::from(1_000_000_000_000u64),
+			gas_price: U256::zero(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+
+		let mut state = get_temp_state_with_factory(factory);
+		state.init_code(&contract, code).unwrap();
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(1_000_000_000_000u64);
+		let machine = make_frontier_machine(0);
+		let schedule = machine.schedule(info.number);
+
+		let started = Instant::now();
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			let opts = TransactOptions::with_no_tracing()
+				.with_deadline(Some(started + Duration::from_millis(50)));
+			ex.transact(&t, opts).unwrap()
+		};
+
+		assert_eq!(executed.exception, Some(vm::Error::Timeout));
+		assert!(started.elapsed() < Duration::from_secs(10));
+	}
+
+	evm_test!{test_transact_dry_run: test_transact_dry_run_int}
+	fn test_transact_dry_run(factory: Factory) {
+		let keypair = Random.generate().unwrap();
+		let t = Transaction {
+			action: Action::Call(Address::from_low_u64_be(0xf00)),
+			value: U256::from(17),
+			data: vec![],
+			gas: U256::from(100_000),
+			gas_price: U256::one(),
+			nonce: U256::zero()
+		}.sign(keypair.secret(), None);
+		let sender = t.sender();
+
+		let mut state = get_temp_state_with_factory(factory);
+		let mut info = EnvInfo::default();
+		info.gas_limit = U256::from(100_000);
+		let machine = make_frontier_machine(0);
+		let schedule = machine.schedule(info.number);
+
+		let executed = {
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			ex.transact_dry_run(&t, TransactOptions::with_no_tracing()).unwrap()
+		};
+
+		assert!(executed.exception.is_none());
+		assert_eq!(executed.gas_used, U256::from(21_000));
+		assert_eq!(state.nonce(&sender).unwrap(), U256::zero());
+		assert_eq!(state.balance(&sender).unwrap(), U256::zero());
+		assert_eq!(state.balance(&Address::from_low_u64_be(0xf00)).unwrap(), U256::zero());
+	}
+
+	evm_test!{test_call_created_contract: test_call_created_contract_int}
+	fn test_call_created_contract(factory: Factory) {
+		// child init code:
+		//
+		// 69 600160005260206000f3 - push 10 bytes of runtime code
+		// 60 00 52 - mstore at 0
+		// 60 0a 60 16 f3 - return 10 bytes from offset 22
+		//
+		// child runtime code returns 1 as a 32-byte word.
+		//
+		// parent code:
+		//
+		// 72 <child init code> 60 00 52 - store 19 bytes of init code at offset 13
+		// 60 13 60 0d 60 00 f0 - create, leaving the child address on the stack
+		// 60 20 60 00 60 00 60 00 60 00 85 61 ffff f1 - call the returned address
+		// 50 60 00 51 60 00 55 - drop success flag, sstore returned word at 0
+		// 60 01 55 00 - sstore child address at 1
+		let code = concat!(
+			"72", "69600160005260206000f3600052600a6016f3", "600052",
+			"6013600d6000f0",
+			"6020600060006000600085", "61ffff", "f1",
+			"50600051600055",
+			"60015500",
+		).from_hex().unwrap();
+
+		let sender = Address::from_str("cd1722f3947def4cf144679da39c4c32bdc35681").unwrap();
+		let address = contract_address(CreateContractAddress::FromSenderAndNonce, &sender, &U256::zero(), &[]).0;
+		let child = contract_address(CreateContractAddress::FromSenderAndNonce, &address, &U256::zero(), &[]).0;
+		let mut params = ActionParams::default();
+		params.address = address.clone();
+		params.code_address = address.clone();
+		params.sender = sender.clone();
+		params.origin = sender.clone();
+		params.gas = U256::from(200_000);
+		params.code = Some(Arc::new(code));
+		params.action_type = ActionType::Call;
+		let mut state = get_temp_state_with_factory(factory);
+		let info = EnvInfo::default();
+		let machine = make_frontier_machine(0);
+		let schedule = machine.schedule(info.number);
+		let mut substate = Substate::new();
+
+		{
+			let mut ex = Executive::new(&mut state, &info, &machine, &schedule);
+			ex.call(params, &mut substate, &mut NoopTracer, &mut NoopVMTracer).unwrap();
+		}
+
+		assert_eq!(*state.code(&child).unwrap().unwrap(), "600160005260206000f3".from_hex().unwrap());
+		assert_eq!(state.storage_at(&address, &H256::zero()).unwrap(), H256::from_low_u64_be(1));
+		assert_eq!(state.storage_at(&address, &H256::from_low_u64_be(1)).unwrap(), H256::from(child));
+	}
+
 	evm_test!{test_keccak: test_keccak_int}
 	fn test_keccak(factory: Factory) {
 		let code = "6064640fffffffff20600055".from_hex().unwrap();
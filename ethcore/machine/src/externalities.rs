@@ -16,7 +16,7 @@
 
 //! Transaction Execution environment.
 
-use std::{cmp, sync::Arc};
+use std::{cmp, sync::Arc, time::Instant};
 
 use ethereum_types::{H256, U256, Address, BigEndianHash};
 use parity_bytes::Bytes;
@@ -91,6 +91,7 @@ pub struct Externalities<'a, T: 'a, V: 'a, B: 'a> {
 	tracer: &'a mut T,
 	vm_tracer: &'a mut V,
 	static_flag: bool,
+	deadline: Option<Instant>,
 }
 
 impl<'a, T: 'a, V: 'a, B: 'a> Externalities<'a, T, V, B>
@@ -124,8 +125,15 @@ impl<'a, T: 'a, V: 'a, B: 'a> Externalities<'a, T, V, B>
 			tracer,
 			vm_tracer,
 			static_flag,
+			deadline: None,
 		}
 	}
+
+	/// Abort execution once `deadline` has passed.
+	pub fn with_deadline(mut self, deadline: Option<Instant>) -> Self {
+		self.deadline = deadline;
+		self
+	}
 }
 
 impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
@@ -278,7 +286,8 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 		}
 
 		// TODO: handle internal error separately
-		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.schedule, self.depth, self.static_flag);
+		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.schedule, self.depth, self.static_flag)
+			.with_deadline(self.deadline);
 		let out = ex.create_with_crossbeam(params, self.substate, self.stack_depth + 1, self.tracer, self.vm_tracer);
 		Ok(into_contract_create_result(out, &address, self.substate))
 	}
@@ -329,7 +338,8 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 			return Err(TrapKind::Call(params));
 		}
 
-		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.schedule, self.depth, self.static_flag);
+		let mut ex = Executive::from_parent(self.state, self.env_info, self.machine, self.schedule, self.depth, self.static_flag)
+			.with_deadline(self.deadline);
 		let out = ex.call_with_crossbeam(params, self.substate, self.stack_depth + 1, self.tracer, self.vm_tracer);
 		Ok(into_message_call_result(out))
 	}
@@ -457,6 +467,10 @@ impl<'a, T: 'a, V: 'a, B: 'a> Ext for Externalities<'a, T, V, B>
 	fn is_static(&self) -> bool {
 		return self.static_flag
 	}
+
+	fn deadline_exceeded(&self) -> bool {
+		self.deadline.map_or(false, |deadline| Instant::now() >= deadline)
+	}
 }
 
 #[cfg(test)]
@@ -35,7 +35,7 @@ use common_types::{
 	transaction::{self, SYSTEM_ADDRESS, UNSIGNED_SENDER, UnverifiedTransaction, SignedTransaction},
 };
 use vm::{ActionType, ActionParams, ActionValue, ParamsType};
-use vm::{EnvInfo, Schedule};
+use vm::{EnvInfo, ForkConfig, Schedule};
 
 use account_state::CleanupMode;
 use client_traits::BlockInfo;
@@ -269,17 +269,31 @@ impl Machine {
 		&self.params
 	}
 
+	/// Activation blocks of the main net forks configured by the chain spec.
+	/// Forks enabled EIP by EIP in the spec activate with their first EIP.
+	pub fn fork_config(&self) -> ForkConfig {
+		let at = |transition: BlockNumber| Some(transition).filter(|n| *n != BlockNumber::max_value());
+		ForkConfig {
+			homestead: self.ethash_extensions.as_ref().map_or(Some(0), |ext| at(ext.homestead_transition)),
+			eip150: at(self.params.eip150_transition),
+			eip158: at(self.params.eip161abc_transition),
+			byzantium: at(self.params.eip140_transition),
+			constantinople: at(self.params.eip1014_transition),
+			istanbul: at(self.params.eip1884_transition),
+		}
+	}
+
 	/// Get the EVM schedule for the given block number.
 	pub fn schedule(&self, block_number: BlockNumber) -> Schedule {
 		let mut schedule = match self.ethash_extensions {
-			None => self.params.schedule(block_number),
-			Some(ref ext) => {
-				if block_number < ext.homestead_transition {
-					Schedule::new_frontier()
-				} else {
-					self.params.schedule(block_number)
-				}
+			// Frontier blocks get the preset picked by the fork config. Later blocks follow the
+			// common params, which may enable forks EIP by EIP.
+			Some(ref ext) if block_number < ext.homestead_transition => {
+				let mut schedule = Schedule::for_block(block_number, &self.fork_config());
+				self.params.apply_gas_overrides(&mut schedule);
+				schedule
 			}
+			_ => self.params.schedule(block_number),
 		};
 
 		if let Some(ref rules) = self.schedule_rules {
@@ -438,6 +452,27 @@ mod tests {
 		assert_eq!(res, Err(transaction::Error::InvalidSignature("invalid EC signature".into())));
 	}
 
+	#[test]
+	fn frontier_schedule_applies_spec_gas_overrides() {
+		let spec = spec::new_frontier_test();
+		let mut params = spec.params().clone();
+		params.tx_gas = Some(30_000);
+		params.tx_data_zero_gas = Some(2);
+
+		let machine = Machine::with_ethash_extensions(params, Default::default(), get_default_ethash_extensions());
+
+		let frontier = machine.schedule(1);
+		assert!(!frontier.have_delegate_call);
+		assert_eq!(frontier.tx_gas, 30_000);
+		assert_eq!(frontier.tx_data_zero_gas, 2);
+		assert_eq!(frontier.tx_create_gas, 21_000);
+
+		let homestead = machine.schedule(1150000);
+		assert!(homestead.have_delegate_call);
+		assert_eq!(homestead.tx_gas, 30_000);
+		assert_eq!(homestead.tx_create_gas, 53_000);
+	}
+
 	#[test]
 	fn ethash_gas_limit_is_multiple_of_determinant() {
 		use ethereum_types::U256;
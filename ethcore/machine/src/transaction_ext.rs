@@ -16,6 +16,7 @@
 
 //! Ethereum transaction
 
+use ethereum_types::U256;
 use evm::Schedule;
 use common_types::transaction::{self, Action};
 
@@ -27,17 +28,63 @@ pub trait Transaction {
 
 impl Transaction for transaction::Transaction {
 	fn gas_required(&self, schedule: &Schedule) -> u64 {
-		gas_required_for(match self.action {
-			Action::Create => true,
-			Action::Call(_) => false
-		}, &self.data, schedule)
+		intrinsic_gas(self, schedule).as_u64()
 	}
 }
 
-/// Get the transaction cost in gas for the given params.
-fn gas_required_for(is_create: bool, data: &[u8], schedule: &Schedule) -> u64 {
-	data.iter().fold(
-		(if is_create {schedule.tx_create_gas} else {schedule.tx_gas}) as u64,
-		|g, b| g + (match *b { 0 => schedule.tx_data_zero_gas, _ => schedule.tx_data_non_zero_gas }) as u64
-	)
+/// Gas charged before any code runs: `tx_gas` (or `tx_create_gas` for contract creation)
+/// plus the schedule's cost of every zero and non-zero byte of the transaction data.
+pub fn intrinsic_gas(t: &transaction::Transaction, schedule: &Schedule) -> U256 {
+	let base = match t.action {
+		Action::Create => schedule.tx_create_gas,
+		Action::Call(_) => schedule.tx_gas,
+	};
+	let zero_bytes = t.data.iter().filter(|b| **b == 0).count();
+	let non_zero_bytes = t.data.len() - zero_bytes;
+
+	U256::from(base)
+		+ U256::from(zero_bytes) * U256::from(schedule.tx_data_zero_gas)
+		+ U256::from(non_zero_bytes) * U256::from(schedule.tx_data_non_zero_gas)
+}
+
+#[cfg(test)]
+mod tests {
+	use ethereum_types::{Address, U256};
+	use evm::Schedule;
+	use common_types::transaction::{Action, Transaction as RawTransaction};
+
+	use super::{intrinsic_gas, Transaction};
+
+	fn transaction(action: Action, data: Vec<u8>) -> RawTransaction {
+		RawTransaction {
+			action,
+			data,
+			nonce: U256::zero(),
+			gas_price: U256::zero(),
+			gas: U256::zero(),
+			value: U256::zero(),
+		}
+	}
+
+	#[test]
+	fn intrinsic_gas_for_empty_data() {
+		let schedule = Schedule::new_homestead();
+		assert_eq!(intrinsic_gas(&transaction(Action::Call(Address::zero()), vec![]), &schedule), U256::from(21_000));
+		assert_eq!(intrinsic_gas(&transaction(Action::Create, vec![]), &schedule), U256::from(53_000));
+	}
+
+	#[test]
+	fn intrinsic_gas_for_zero_data() {
+		let schedule = Schedule::new_homestead();
+		let t = transaction(Action::Call(Address::zero()), vec![0; 10]);
+		assert_eq!(intrinsic_gas(&t, &schedule), U256::from(21_000 + 10 * 4));
+	}
+
+	#[test]
+	fn intrinsic_gas_for_mixed_data_across_forks() {
+		let t = transaction(Action::Call(Address::zero()), vec![0, 1, 0, 2, 3]);
+		assert_eq!(intrinsic_gas(&t, &Schedule::new_homestead()), U256::from(21_000 + 2 * 4 + 3 * 68));
+		assert_eq!(intrinsic_gas(&t, &Schedule::new_istanbul()), U256::from(21_000 + 2 * 4 + 3 * 16));
+		assert_eq!(t.gas_required(&Schedule::new_istanbul()), 21_000 + 2 * 4 + 3 * 16);
+	}
 }
@@ -150,7 +150,7 @@ pub fn snap(client: &Client) -> (Box<dyn SnapshotReader>, TempDir) {
 	let progress = RwLock::new(Progress::new());
 
 	let hash = client.chain_info().best_block_hash;
-	client.take_snapshot(writer, BlockId::Hash(hash), &progress).unwrap();
+	client.take_snapshot(writer, BlockId::Hash(hash), &progress, None, &|_| ()).unwrap();
 
 	let reader = PackedReader::new(&path).unwrap().unwrap();
 
@@ -16,14 +16,19 @@
 
 //! Tests for snapshot i/o.
 
+use std::fs;
+
 use tempdir::TempDir;
 use keccak_hash::keccak;
 
-use common_types::snapshot::ManifestData;
+use common_types::{
+	errors::SnapshotError,
+	snapshot::ManifestData,
+};
 use snapshot::io::{
 	SnapshotWriter,SnapshotReader,
 	PackedWriter, PackedReader, LooseWriter, LooseReader,
-	SNAPSHOT_VERSION,
+	SNAPSHOT_VERSION, read_chunk,
 };
 
 const STATE_CHUNKS: &'static [&'static [u8]] = &[b"dog", b"cat", b"hello world", b"hi", b"notarealchunk"];
@@ -107,3 +112,29 @@ fn loose_write_and_read() {
 		reader.chunk(hash.clone()).unwrap();
 	}
 }
+
+#[test]
+fn read_chunk_detects_tampering() {
+	let tempdir = TempDir::new("").unwrap();
+	let mut writer = LooseWriter::new(tempdir.path().into()).unwrap();
+
+	let compressed = snappy::compress(b"hello world");
+	let hash = keccak(&compressed);
+	writer.write_state_chunk(hash, &compressed).unwrap();
+
+	let path = tempdir.path().join(format!("{:x}", hash));
+	assert_eq!(read_chunk(&path).unwrap(), (hash, b"hello world".to_vec()));
+
+	let mut tampered = fs::read(&path).unwrap();
+	let last = tampered.len() - 1;
+	tampered[last] ^= 0xff;
+	fs::write(&path, &tampered).unwrap();
+
+	match read_chunk(&path) {
+		Err(SnapshotError::ChunkHashMismatch(expected, found)) => {
+			assert_eq!(expected, hash);
+			assert_eq!(found, keccak(&tampered));
+		}
+		other => panic!("expected a chunk hash mismatch, got {:?}", other),
+	}
+}
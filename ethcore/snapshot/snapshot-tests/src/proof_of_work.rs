@@ -74,7 +74,8 @@ fn chunk_and_restore(amount: u64) {
 		&bc,
 		best_hash,
 		&writer,
-		&RwLock::new(Progress::new())
+		&RwLock::new(Progress::new()),
+		&|_| (),
 	).unwrap();
 
 	let manifest = ManifestData {
@@ -119,6 +120,46 @@ fn chunk_and_restore_4k() {
 	chunk_and_restore(4000)
 }
 
+#[test]
+fn chunking_stops_when_aborted() {
+	let genesis = BlockBuilder::genesis();
+	let rest = genesis.add_blocks(500);
+	let generator = BlockGenerator::new(vec![rest]);
+	let genesis = genesis.last();
+
+	let db = test_helpers::new_db();
+	let bc = BlockChain::new(Default::default(), genesis.encoded().raw(), db.clone());
+
+	let mut batch = DBTransaction::new();
+	for block in generator {
+		bc.insert_block(&mut batch, block.encoded(), vec![], ExtrasInsert {
+			fork_choice: ForkChoice::New,
+			is_finalized: false,
+		});
+		bc.commit();
+	}
+	db.key_value().write(batch).unwrap();
+
+	let progress = RwLock::new(Progress::new());
+	let mut chunks = 0;
+	let result = {
+		// request an abort as soon as the first chunk has been written.
+		let mut chunk_sink = |_: &[u8]| {
+			chunks += 1;
+			progress.write().abort = true;
+			Ok::<_, std::io::Error>(())
+		};
+
+		let mut snapshot_mode = SNAPSHOT_MODE;
+		snapshot_mode.chunk_all(&bc, bc.best_block_hash(), &mut chunk_sink, &progress, 4096)
+	};
+
+	// chunking returns cleanly, keeping the partial output.
+	assert!(result.is_ok());
+	assert_eq!(chunks, 1);
+	assert!(progress.read().blocks() < 500);
+}
+
 #[test]
 fn checks_flag() {
 	use rlp::RlpStream;
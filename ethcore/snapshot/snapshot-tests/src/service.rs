@@ -173,9 +173,12 @@ fn restored_is_equivalent() {
 	};
 
 	let service = Service::new(service_params).unwrap();
-	service.take_snapshot(&*client, NUM_BLOCKS as u64).unwrap();
+	let reports = Mutex::new(Vec::new());
+	service.take_snapshot_with_progress(&*client, NUM_BLOCKS as u64, &|progress| reports.lock().push(progress)).unwrap();
 
 	let manifest = service.manifest().unwrap();
+	// one report per chunk written.
+	assert_eq!(reports.lock().len(), manifest.state_hashes.len() + manifest.block_hashes.len());
 
 	service.init_restore(manifest.clone(), true).unwrap();
 	assert!(service.init_restore(manifest.clone(), true).is_ok());
@@ -278,7 +281,8 @@ fn keep_ancient_blocks() {
 		&bc,
 		best_hash,
 		&writer,
-		&RwLock::new(Progress::new())
+		&RwLock::new(Progress::new()),
+		&|_| (),
 	).unwrap();
 	let state_db = client.state_db().journal_db().boxed_clone();
 	let start_header = bc.block_header_data(&best_hash).unwrap();
@@ -288,6 +292,7 @@ fn keep_ancient_blocks() {
 		&state_root,
 		&writer,
 		&RwLock::new(Progress::new()),
+		&|_| (),
 		None,
 		0
 	).unwrap();
@@ -446,3 +451,76 @@ fn recover_aborted_recovery() {
 		_ => panic!("Snapshot restoration must be ongoing"),
 	}
 }
+
+#[test]
+fn manifest_independent_of_thread_count() {
+	const NUM_BLOCKS: u32 = 20;
+	const SNAPSHOT_MODE: PowSnapshot = PowSnapshot { blocks: 10, max_restore_blocks: 10 };
+
+	let gas_prices = vec![1.into(), 2.into(), 3.into(), 999.into()];
+	let client = generate_dummy_client_with_spec_and_data(spec::new_null, NUM_BLOCKS, 5, &gas_prices, false);
+	let bc = client.chain();
+	let best_hash = bc.best_block_hash();
+	let state_db = client.state_db().journal_db().boxed_clone();
+
+	let tempdir = TempDir::new("").unwrap();
+	let manifest_with_threads = |threads: usize| {
+		let path = tempdir.path().join(format!("SNAP-{}", threads));
+		let writer = PackedWriter::new(&path).unwrap();
+		snapshot::take_snapshot(
+			Box::new(SNAPSHOT_MODE),
+			&bc,
+			best_hash,
+			state_db.as_hash_db(),
+			writer,
+			&RwLock::new(Progress::new()),
+			threads,
+			None,
+			&|_| (),
+		).unwrap();
+
+		PackedReader::new(&path).unwrap().unwrap().manifest().clone()
+	};
+
+	let single = manifest_with_threads(1);
+	let multi = manifest_with_threads(4);
+	assert_eq!(single.state_hashes, multi.state_hashes);
+	assert_eq!(single.into_rlp(), multi.into_rlp());
+}
+
+#[test]
+fn snapshot_reuses_previous_snapshot() {
+	const NUM_BLOCKS: u32 = 20;
+	const SNAPSHOT_MODE: PowSnapshot = PowSnapshot { blocks: 10, max_restore_blocks: 10 };
+
+	let gas_prices = vec![1.into(), 2.into(), 3.into(), 999.into()];
+	let client = generate_dummy_client_with_spec_and_data(spec::new_null, NUM_BLOCKS, 5, &gas_prices, false);
+	let bc = client.chain();
+	let best_hash = bc.best_block_hash();
+	let state_db = client.state_db().journal_db().boxed_clone();
+
+	let tempdir = TempDir::new("").unwrap();
+	let take = |name: &str, previous: Option<&(dyn SnapshotReader + Sync)>| {
+		let path = tempdir.path().join(name);
+		snapshot::take_snapshot(
+			Box::new(SNAPSHOT_MODE),
+			&bc,
+			best_hash,
+			state_db.as_hash_db(),
+			PackedWriter::new(&path).unwrap(),
+			&RwLock::new(Progress::new()),
+			2,
+			previous,
+			&|_| (),
+		).unwrap();
+
+		PackedReader::new(&path).unwrap().unwrap()
+	};
+
+	let full = take("FULL", None);
+	let incremental = take("INCREMENTAL", Some(&full));
+	assert_eq!(incremental.manifest(), full.manifest());
+	for hash in &full.manifest().state_hashes {
+		assert_eq!(incremental.chunk(*hash).unwrap(), full.chunk(*hash).unwrap());
+	}
+}
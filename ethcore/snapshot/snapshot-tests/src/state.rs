@@ -17,7 +17,7 @@
 //! State snapshotting tests.
 
 use std::sync::Arc;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
 use keccak_hash::{KECCAK_NULL_RLP, keccak};
 use common_types::{
@@ -27,13 +27,16 @@ use common_types::{
 };
 use snapshot::{
 	test_helpers::to_fat_rlps,
-	chunk_state, StateRebuilder, SNAPSHOT_SUBPARTS,
+	chunk_state, chunk_state_incremental, PreviousState, StateRebuilder, SNAPSHOT_SUBPARTS,
 	io::{PackedReader, PackedWriter, SnapshotReader, SnapshotWriter},
 };
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
 use ethereum_types::H256;
+use hash_db::{AsHashDB, HashDB, Prefix};
 use journaldb::{self, Algorithm};
+use keccak_hasher::KeccakHasher;
+use kvdb::DBValue;
 use kvdb_rocksdb::{Database, DatabaseConfig};
 use parking_lot::{Mutex, RwLock};
 use tempdir::TempDir;
@@ -42,9 +45,53 @@ use crate::helpers::StateProducer;
 
 const RNG_SEED: [u8; 16] = [1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16];
 
+/// Read-only view of a state database counting the nodes read through it.
+struct CountingDB<'a> {
+	inner: &'a dyn HashDB<KeccakHasher, DBValue>,
+	reads: AtomicUsize,
+}
+
+impl<'a> CountingDB<'a> {
+	fn new(inner: &'a dyn HashDB<KeccakHasher, DBValue>) -> Self {
+		CountingDB { inner, reads: AtomicUsize::new(0) }
+	}
+
+	fn reads(&self) -> usize {
+		self.reads.load(Ordering::SeqCst)
+	}
+}
+
+impl<'a> HashDB<KeccakHasher, DBValue> for CountingDB<'a> {
+	fn get(&self, key: &H256, prefix: Prefix) -> Option<DBValue> {
+		self.reads.fetch_add(1, Ordering::SeqCst);
+		self.inner.get(key, prefix)
+	}
+
+	fn contains(&self, key: &H256, prefix: Prefix) -> bool {
+		self.inner.contains(key, prefix)
+	}
+
+	fn insert(&mut self, _prefix: Prefix, _value: &[u8]) -> H256 {
+		panic!("CountingDB only wraps a shared reference, snapshots never write to the state");
+	}
+
+	fn emplace(&mut self, _key: H256, _prefix: Prefix, _value: DBValue) {
+		panic!("CountingDB only wraps a shared reference, snapshots never write to the state");
+	}
+
+	fn remove(&mut self, _key: &H256, _prefix: Prefix) {
+		panic!("CountingDB only wraps a shared reference, snapshots never write to the state");
+	}
+}
+
+impl<'a> AsHashDB<KeccakHasher, DBValue> for CountingDB<'a> {
+	fn as_hash_db(&self) -> &dyn HashDB<KeccakHasher, DBValue> { self }
+	fn as_hash_db_mut<'b>(&'b mut self) -> &'b mut (dyn HashDB<KeccakHasher, DBValue> + 'b) { self }
+}
+
 #[test]
 fn snap_and_restore() {
-	use hash_db::{HashDB, EMPTY_PREFIX};
+	use hash_db::EMPTY_PREFIX;
 	let mut producer = StateProducer::new();
 	let mut rng = XorShiftRng::from_seed(RNG_SEED);
 	let mut old_db = journaldb::new_memory_db();
@@ -63,7 +110,7 @@ fn snap_and_restore() {
 	let mut state_hashes = Vec::new();
 	let progress = RwLock::new(Progress::new());
 	for part in 0..SNAPSHOT_SUBPARTS {
-		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, Some(part), 0).unwrap();
+		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, &|_| (), Some(part), 0).unwrap();
 		state_hashes.append(&mut hashes);
 	}
 
@@ -106,12 +153,157 @@ fn snap_and_restore() {
 	}
 }
 
+#[test]
+fn incremental_snapshot_reuses_unchanged_parts() {
+	use ethereum_types::U256;
+	use ethtrie::{TrieDB, TrieDBMut};
+	use hash_db::EMPTY_PREFIX;
+	use trie_db::{Trie, TrieMut};
+
+	let mut producer = StateProducer::new();
+	let mut rng = XorShiftRng::from_seed(RNG_SEED);
+	let mut db = journaldb::new_memory_db();
+
+	for _ in 0..150 {
+		producer.tick(&mut rng, &mut db);
+	}
+
+	let tempdir = TempDir::new("").unwrap();
+	let progress = RwLock::new(Progress::new());
+	let old_root = producer.state_root();
+	let old_file = tempdir.path().join("OLD");
+	let writer = Mutex::new(PackedWriter::new(&old_file).unwrap());
+	let old_parts: Vec<_> = (0..SNAPSHOT_SUBPARTS)
+		.map(|part| chunk_state(&db, &old_root, &writer, &progress, &|_| (), Some(part), 0).unwrap())
+		.collect();
+
+	writer.into_inner().finish(ManifestData {
+		version: 2,
+		state_hashes: old_parts.concat(),
+		block_hashes: Vec::new(),
+		state_root: old_root,
+		block_number: 1000,
+		block_hash: H256::zero(),
+	}).unwrap();
+	let reader = PackedReader::new(&old_file).unwrap().unwrap();
+	let previous = PreviousState::new(&reader).unwrap().unwrap();
+
+	// bump the balance of a single account. The old root node is pruned by the trie,
+	// so put it back as an archive database would keep it.
+	let old_root_node = db.get(&old_root, EMPTY_PREFIX).unwrap();
+	let (key, value) = TrieDB::new(&db, &old_root).unwrap().iter().unwrap().next().unwrap().unwrap();
+	let mut account: BasicAccount = ::rlp::decode(&value).unwrap();
+	account.balance = account.balance + U256::one();
+
+	let mut new_root = old_root;
+	TrieDBMut::from_existing(&mut db, &mut new_root).unwrap()
+		.insert(&key, &::rlp::encode(&account)).unwrap();
+	db.insert(EMPTY_PREFIX, &old_root_node);
+	let changed_part = key[0] as usize * SNAPSHOT_SUBPARTS / 256;
+
+	let full_db = CountingDB::new(&db);
+	let writer = Mutex::new(PackedWriter::new(&tempdir.path().join("FULL")).unwrap());
+	let full_parts: Vec<_> = (0..SNAPSHOT_SUBPARTS)
+		.map(|part| chunk_state(&full_db, &new_root, &writer, &progress, &|_| (), Some(part), 0).unwrap())
+		.collect();
+
+	let incremental_db = CountingDB::new(&db);
+	let writer = Mutex::new(PackedWriter::new(&tempdir.path().join("NEW")).unwrap());
+	let new_parts: Vec<_> = (0..SNAPSHOT_SUBPARTS)
+		.map(|part| chunk_state_incremental(&incremental_db, &new_root, &previous, &writer, &progress, &|_| (), part, 0).unwrap())
+		.collect();
+
+	assert_eq!(new_parts, full_parts);
+	for part in 0..SNAPSHOT_SUBPARTS {
+		if part == changed_part {
+			assert_ne!(new_parts[part], old_parts[part]);
+		} else {
+			assert_eq!(new_parts[part], old_parts[part]);
+		}
+	}
+
+	// only the changed part is walked again.
+	assert!(incremental_db.reads() * 4 < full_db.reads(), "{} reads out of {}", incremental_db.reads(), full_db.reads());
+}
+
+#[test]
+fn incremental_snapshot_needs_chunks_ordered_by_part() {
+	let mut producer = StateProducer::new();
+	let mut rng = XorShiftRng::from_seed(RNG_SEED);
+	let mut db = journaldb::new_memory_db();
+
+	for _ in 0..50 {
+		producer.tick(&mut rng, &mut db);
+	}
+
+	let tempdir = TempDir::new("").unwrap();
+	let state_root = producer.state_root();
+	let progress = RwLock::new(Progress::new());
+	let snapshot = |name: &str, parts: Vec<usize>| {
+		let path = tempdir.path().join(name);
+		let writer = Mutex::new(PackedWriter::new(&path).unwrap());
+		let state_hashes = parts.into_iter()
+			.flat_map(|part| chunk_state(&db, &state_root, &writer, &progress, &|_| (), Some(part), 0).unwrap())
+			.collect();
+
+		writer.into_inner().finish(ManifestData {
+			version: 2,
+			state_hashes,
+			block_hashes: Vec::new(),
+			state_root,
+			block_number: 1000,
+			block_hash: H256::zero(),
+		}).unwrap();
+		PackedReader::new(&path).unwrap().unwrap()
+	};
+
+	let ordered = snapshot("ORDERED", (0..SNAPSHOT_SUBPARTS).collect());
+	assert!(PreviousState::new(&ordered).unwrap().is_some());
+
+	// chunks laid out per thread, as older snapshots taken with two threads were.
+	let per_thread = snapshot("PER-THREAD", (0..SNAPSHOT_SUBPARTS).step_by(2).chain((1..SNAPSHOT_SUBPARTS).step_by(2)).collect());
+	assert!(PreviousState::new(&per_thread).unwrap().is_none());
+}
+
+#[test]
+fn chunking_stops_when_aborted() {
+	let mut producer = StateProducer::new();
+	let mut rng = XorShiftRng::from_seed(RNG_SEED);
+	let mut old_db = journaldb::new_memory_db();
+
+	for _ in 0..150 {
+		producer.tick(&mut rng, &mut old_db);
+	}
+
+	let tempdir = TempDir::new("").unwrap();
+	let state_root = producer.state_root();
+	let writer = Mutex::new(PackedWriter::new(&tempdir.path().join("SNAP")).unwrap());
+	let progress = RwLock::new(Progress::new());
+	let reported = Mutex::new(Vec::new());
+
+	// every part writes at least one chunk, abort as soon as the first one is out.
+	let on_progress = |p: Progress| {
+		reported.lock().push(p.accounts());
+		progress.write().abort = true;
+	};
+
+	let mut state_hashes = Vec::new();
+	for part in 0..SNAPSHOT_SUBPARTS {
+		let mut hashes = chunk_state(&old_db, &state_root, &writer, &progress, &on_progress, Some(part), 0).unwrap();
+		state_hashes.append(&mut hashes);
+	}
+
+	assert_eq!(state_hashes.len(), 1);
+	assert_eq!(*reported.lock(), vec![progress.read().accounts()]);
+	assert!(progress.read().accounts() > 0);
+}
+
 #[test]
 fn get_code_from_prev_chunk() {
 	use std::collections::HashSet;
 	use rlp::RlpStream;
 	use ethereum_types::{H256, U256};
-	use hash_db::{HashDB, EMPTY_PREFIX};
+	use hash_db::EMPTY_PREFIX;
 
 	use account_db::{AccountDBMut, AccountDB};
 
@@ -188,7 +380,7 @@ fn checks_flag() {
 	let writer = Mutex::new(PackedWriter::new(&snap_file).unwrap());
 	let progress = RwLock::new(Progress::new());
 
-	let state_hashes = chunk_state(&old_db, &state_root, &writer, &progress, None, 0).unwrap();
+	let state_hashes = chunk_state(&old_db, &state_root, &writer, &progress, &|_| (), None, 0).unwrap();
 
 	writer.into_inner().finish(ManifestData {
 		version: 2,
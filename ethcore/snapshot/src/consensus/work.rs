@@ -126,6 +126,10 @@ impl<'a> PowWorker<'a> {
 
 		for _ in 0..snapshot_blocks {
 			if self.current_hash == genesis_hash { break }
+			if self.progress.read().abort {
+				trace!(target: "snapshot", "block chunker: aborting snapshot");
+				return Ok(());
+			}
 
 			let (block, receipts) = self.chain.block(&self.current_hash)
 				.and_then(|b| self.chain.block_receipts(&self.current_hash).map(|r| (b, r)))
@@ -31,6 +31,7 @@ use common_types::{
 	snapshot::ManifestData,
 };
 use ethereum_types::H256;
+use keccak_hash::keccak;
 use log::trace;
 use rlp::{RlpStream, Rlp};
 use rlp_derive::*;
@@ -331,3 +332,28 @@ impl SnapshotReader for LooseReader {
 		Ok(buf)
 	}
 }
+
+/// Read a single chunk file, named by the hex-encoded hash of its contents,
+/// as written by `LooseWriter`.
+///
+/// The hash of the file contents is checked against the file name before the
+/// chunk is decompressed. Returns the verified hash and the decompressed chunk.
+pub fn read_chunk(path: &Path) -> Result<(H256, Bytes), SnapshotError> {
+	let expected: H256 = path.file_name()
+		.and_then(|name| name.to_str())
+		.and_then(|name| name.parse().ok())
+		.ok_or_else(|| SnapshotError::WrongChunkFormat(format!("invalid chunk file name: {}", path.display())))?;
+
+	let mut compressed = Vec::new();
+	File::open(path)?.read_to_end(&mut compressed)?;
+
+	let found = keccak(&compressed);
+	if found != expected {
+		return Err(SnapshotError::ChunkHashMismatch(expected, found));
+	}
+
+	let chunk = snappy::decompress(&compressed)
+		.map_err(|e| SnapshotError::WrongChunkFormat(format!("{}", e)))?;
+
+	Ok((expected, chunk))
+}
@@ -41,7 +41,7 @@ use crossbeam_utils::thread;
 use engine::Engine;
 use ethereum_types::{H256, U256};
 use ethtrie::{TrieDB, TrieDBMut};
-use hash_db::HashDB;
+use hash_db::{HashDB, EMPTY_PREFIX};
 use journaldb::{self, Algorithm, JournalDB};
 use keccak_hasher::KeccakHasher;
 use parking_lot::{Mutex, RwLock};
@@ -58,6 +58,7 @@ pub use self::consensus::*;
 pub use self::service::{Service, Guard, Restoration, RestorationParams};
 pub use self::traits::{Broadcast, Oracle, SnapshotService, SnapshotClient, SnapshotComponents, Rebuilder};
 pub use self::io::SnapshotWriter;
+use self::io::SnapshotReader;
 pub use self::watcher::Watcher;
 use common_types::basic_account::BasicAccount;
 
@@ -115,6 +116,14 @@ impl Default for SnapshotConfiguration {
 }
 
 /// Take a snapshot using the given blockchain, starting block hash, and database, writing into the given writer.
+///
+/// With a `previous` snapshot, the parts of the state which did not change since are copied over
+/// from it rather than chunked again, as long as its state is still in `state_db`.
+///
+/// `on_progress` is called with the updated progress after every chunk written, from the thread
+/// which wrote it. Setting `abort` on `p` cancels the snapshot: every chunker stops before its
+/// next chunk and returns the chunks produced so far, which are left in `writer`, and no manifest
+/// is written. The snapshot then fails with `SnapshotAborted`.
 pub fn take_snapshot<W: SnapshotWriter + Send>(
 	chunker: Box<dyn SnapshotComponents>,
 	chain: &BlockChain,
@@ -123,6 +132,8 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 	writer: W,
 	p: &RwLock<Progress>,
 	processing_threads: usize,
+	previous: Option<&(dyn SnapshotReader + Sync)>,
+	on_progress: &(dyn Fn(Progress) + Sync),
 ) -> Result<(), Error> {
 	let start_header = chain.block_header_data(&block_hash)
 		.ok_or_else(|| Error::InvalidStartingBlock(BlockId::Hash(block_hash)))?;
@@ -131,12 +142,18 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 
 	info!("Taking snapshot starting at block #{}/{:?}", block_number, block_hash);
 	let version = chunker.current_version();
+	// without the previous state root there is nothing to compare the parts against.
+	let previous = match previous {
+		Some(reader) if state_db.contains(&reader.manifest().state_root, EMPTY_PREFIX) => PreviousState::new(reader)?,
+		_ => None,
+	};
+	let previous = previous.as_ref();
 	let writer = Mutex::new(writer);
 	let (state_hashes, block_hashes) = thread::scope(|scope| -> Result<(Vec<H256>, Vec<H256>), Error> {
 		let writer = &writer;
 		let tb = scope.builder().name("Snapshot Worker - Blocks".to_string());
 		let block_guard = tb.spawn(move |_| {
-			chunk_secondary(chunker, chain, block_hash, writer, p)
+			chunk_secondary(chunker, chain, block_hash, writer, p, on_progress)
 		})?;
 
 		// The number of threads must be between 1 and SNAPSHOT_SUBPARTS
@@ -148,31 +165,44 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 
 		for thread_idx in 0..num_threads {
 			let tb = scope.builder().name(format!("Snapshot Worker #{} - State", thread_idx).to_string());
-			let state_guard = tb.spawn(move |_| -> Result<Vec<H256>, Error> {
-				let mut chunk_hashes = Vec::new();
+			let state_guard = tb.spawn(move |_| -> Result<Vec<(usize, Vec<H256>)>, Error> {
+				let mut part_hashes = Vec::new();
 				for part in (thread_idx..SNAPSHOT_SUBPARTS).step_by(num_threads) {
 					debug!(target: "snapshot", "Chunking part {} of the state at {} in thread {}", part, block_number, thread_idx);
-					let mut hashes = chunk_state(state_db, &state_root, writer, p, Some(part), thread_idx)?;
-					chunk_hashes.append(&mut hashes);
+					let hashes = match previous {
+						Some(previous) => chunk_state_incremental(state_db, &state_root, previous, writer, p, on_progress, part, thread_idx)?,
+						None => chunk_state(state_db, &state_root, writer, p, on_progress, Some(part), thread_idx)?,
+					};
+					part_hashes.push((part, hashes));
 				}
-				Ok(chunk_hashes)
+				Ok(part_hashes)
 			})?;
 			state_guards.push(state_guard);
 		}
 
 		let block_hashes = block_guard.join().expect("Sub-thread never panics; qed")?;
-		let mut state_hashes = Vec::new();
+		let mut part_hashes = Vec::with_capacity(SNAPSHOT_SUBPARTS);
 
 		for guard in state_guards {
-			let part_state_hashes = guard.join().expect("Sub-thread never panics; qed")?;
-			state_hashes.extend(part_state_hashes);
+			let thread_part_hashes = guard.join().expect("Sub-thread never panics; qed")?;
+			part_hashes.extend(thread_part_hashes);
 		}
 
+		// Order the state chunks by part, so the manifest does not depend on how
+		// parts were distributed across threads.
+		part_hashes.sort_by_key(|&(part, _)| part);
+		let state_hashes = part_hashes.into_iter().flat_map(|(_, hashes)| hashes).collect();
+
 		info!("Took a snapshot at #{} of {} accounts", block_number, p.read().accounts());
 
 		Ok((state_hashes, block_hashes))
 	}).expect("Sub-thread never panics; qed")?;
 
+	// the chunkers return early with the chunks written so far, which must not end up in a manifest.
+	if p.read().abort {
+		return Err(Error::SnapshotAborted);
+	}
+
 	info!(target: "snapshot", "produced {} state chunks and {} block chunks.", state_hashes.len(), block_hashes.len());
 
 	let manifest_data = ManifestData {
@@ -197,12 +227,16 @@ pub fn take_snapshot<W: SnapshotWriter + Send>(
 /// Secondary chunks are engine-specific, but they intend to corroborate the state data
 /// in the state chunks.
 /// Returns a list of chunk hashes, with the first having the blocks furthest from the genesis.
+///
+/// `on_progress` is called with the updated progress after every chunk written. If `progress`
+/// is flagged to abort, chunking stops early and the hashes of the chunks written so far are returned.
 pub fn chunk_secondary<'a>(
 	mut chunker: Box<dyn SnapshotComponents>,
 	chain: &'a BlockChain,
 	start_hash: H256,
 	writer: &Mutex<dyn SnapshotWriter + 'a>,
-	progress: &'a RwLock<Progress>
+	progress: &'a RwLock<Progress>,
+	on_progress: &dyn Fn(Progress),
 ) -> Result<Vec<H256>, Error> {
 	let mut chunk_hashes = Vec::new();
 	let mut snappy_buffer = vec![0; snappy::max_compressed_len(PREFERRED_CHUNK_SIZE)];
@@ -220,6 +254,8 @@ pub fn chunk_secondary<'a>(
 
 			progress.write().update(0, size as u64);
 			chunk_hashes.push(hash);
+			let current = progress.read().clone();
+			on_progress(current);
 			Ok(())
 		};
 
@@ -243,6 +279,7 @@ struct StateChunker<'a> {
 	snappy_buffer: Vec<u8>,
 	writer: &'a Mutex<dyn SnapshotWriter + 'a>,
 	progress: &'a RwLock<Progress>,
+	on_progress: &'a dyn Fn(Progress),
 	thread_idx: usize,
 }
 
@@ -280,6 +317,9 @@ impl<'a> StateChunker<'a> {
 		self.hashes.push(hash);
 		self.cur_size = 0;
 
+		let current = self.progress.read().clone();
+		(self.on_progress)(current);
+
 		Ok(())
 	}
 
@@ -294,13 +334,17 @@ impl<'a> StateChunker<'a> {
 /// `part` is a number between 0 and 15, which describe which part of
 /// the tree should be chunked.
 ///
+/// `on_progress` is called with the updated progress after every chunk written.
+///
 /// Returns a list of hashes of chunks created, or any error it may
-/// have encountered.
+/// have encountered. If `progress` is flagged to abort, chunking stops
+/// early and the hashes of the chunks written so far are returned.
 pub fn chunk_state<'a>(
 	db: &dyn HashDB<KeccakHasher, DBValue>,
 	root: &H256,
 	writer: &Mutex<dyn SnapshotWriter + 'a>,
 	progress: &'a RwLock<Progress>,
+	on_progress: &'a dyn Fn(Progress),
 	part: Option<usize>,
 	thread_idx: usize,
 ) -> Result<Vec<H256>, Error> {
@@ -313,6 +357,7 @@ pub fn chunk_state<'a>(
 		snappy_buffer: vec![0; snappy::max_compressed_len(PREFERRED_CHUNK_SIZE)],
 		writer,
 		progress,
+		on_progress,
 		thread_idx,
 	};
 
@@ -338,6 +383,11 @@ pub fn chunk_state<'a>(
 	}
 
 	for item in account_iter {
+		if progress.read().abort {
+			trace!(target: "snapshot", "chunk_state: aborting snapshot");
+			return Ok(chunker.hashes);
+		}
+
 		let (account_key, account_data) = item?;
 		let account_key_hash = H256::from_slice(&account_key);
 
@@ -348,7 +398,7 @@ pub fn chunk_state<'a>(
 		let account = ::rlp::decode(&*account_data)?;
 		let account_db = AccountDB::from_hash(db, account_key_hash);
 
-		let fat_rlps = account::to_fat_rlps(
+		let fat_rlps = match account::to_fat_rlps(
 			&account_key_hash,
 			&account,
 			&account_db,
@@ -356,7 +406,12 @@ pub fn chunk_state<'a>(
 			PREFERRED_CHUNK_SIZE - chunker.chunk_size(),
 			PREFERRED_CHUNK_SIZE,
 			progress
-		)?;
+		) {
+			Ok(fat_rlps) => fat_rlps,
+			// aborted halfway through the account's storage.
+			Err(Error::SnapshotAborted) => return Ok(chunker.hashes),
+			Err(e) => return Err(e),
+		};
 		for (i, fat_rlp) in fat_rlps.into_iter().enumerate() {
 			if i > 0 {
 				chunker.write_chunk()?;
@@ -372,6 +427,118 @@ pub fn chunk_state<'a>(
 	Ok(chunker.hashes)
 }
 
+/// State chunks of a previous snapshot, grouped by part, for `chunk_state_incremental` to reuse.
+pub struct PreviousState<'a> {
+	reader: &'a (dyn SnapshotReader + Sync),
+	state_root: H256,
+	// hash and number of entries of every chunk, by part.
+	parts: Vec<Vec<(H256, usize)>>,
+}
+
+impl<'a> PreviousState<'a> {
+	/// Group the state chunks of the snapshot in `reader` by part.
+	///
+	/// Returns `None` if the chunks can't be reused: the snapshot uses another state chunk format,
+	/// or its manifest does not list the state chunks ordered by part, like the manifests of older
+	/// snapshots taken with several threads.
+	pub fn new(reader: &'a (dyn SnapshotReader + Sync)) -> Result<Option<Self>, Error> {
+		let manifest = reader.manifest();
+		if manifest.version != STATE_CHUNK_VERSION {
+			return Ok(None);
+		}
+
+		let part_offset = MAX_SNAPSHOT_SUBPARTS / SNAPSHOT_SUBPARTS;
+		let mut parts = vec![Vec::new(); SNAPSHOT_SUBPARTS];
+		let mut last_part = 0;
+
+		for hash in &manifest.state_hashes {
+			let raw = decompress_chunk(&reader.chunk(*hash)?)?;
+			let chunk = Rlp::new(&raw);
+			let account_hash: H256 = chunk.at(0)?.val_at(0)?;
+
+			let part = account_hash.as_bytes()[0] as usize / part_offset;
+			if part < last_part {
+				debug!(target: "snapshot", "State chunks of the previous snapshot are not ordered by part, not reusing them");
+				return Ok(None);
+			}
+			last_part = part;
+			parts[part].push((*hash, chunk.item_count()?));
+		}
+
+		Ok(Some(PreviousState {
+			reader,
+			state_root: manifest.state_root,
+			parts,
+		}))
+	}
+}
+
+/// Chunk the given part of the state, reusing the chunks of `previous` when the part did not change.
+///
+/// A part is the subtrie under one child of the state root, and its chunks only depend on that
+/// subtrie. When the child is the same under both roots, the part's chunks are copied over from
+/// the previous snapshot instead of walking the accounts again. Otherwise, or when the previous
+/// state root is no longer in `db`, the part is chunked from scratch with `chunk_state`.
+///
+/// Returns the same list of hashes `chunk_state` would for this part.
+pub fn chunk_state_incremental<'a>(
+	db: &dyn HashDB<KeccakHasher, DBValue>,
+	root: &H256,
+	previous: &PreviousState,
+	writer: &Mutex<dyn SnapshotWriter + 'a>,
+	progress: &'a RwLock<Progress>,
+	on_progress: &'a dyn Fn(Progress),
+	part: usize,
+	thread_idx: usize,
+) -> Result<Vec<H256>, Error> {
+	assert!(part < SNAPSHOT_SUBPARTS, "Wrong chunk state part number (must be <16) in snapshot creation.");
+
+	let unchanged = match (part_subtrie(db, root, part), part_subtrie(db, &previous.state_root, part)) {
+		(Some(current), Some(prev)) => current == prev,
+		_ => false,
+	};
+
+	if !unchanged {
+		return chunk_state(db, root, writer, progress, on_progress, Some(part), thread_idx);
+	}
+
+	let chunks = &previous.parts[part];
+	trace!(target: "snapshot", "Thread {} reusing {} state chunks of part {}", thread_idx, chunks.len(), part);
+
+	let mut hashes = Vec::with_capacity(chunks.len());
+	for &(hash, num_entries) in chunks {
+		if progress.read().abort {
+			trace!(target: "snapshot", "chunk_state_incremental: aborting snapshot");
+			return Ok(hashes);
+		}
+
+		let compressed = previous.reader.chunk(hash)?;
+		writer.lock().write_state_chunk(hash, &compressed)?;
+		progress.write().update(num_entries as u64, compressed.len() as u64);
+		hashes.push(hash);
+
+		let current = progress.read().clone();
+		on_progress(current);
+	}
+
+	Ok(hashes)
+}
+
+// Raw reference to the child of the state root holding the accounts of `part`,
+// if the root is a branch node found in `db`.
+fn part_subtrie(db: &dyn HashDB<KeccakHasher, DBValue>, root: &H256, part: usize) -> Option<Bytes> {
+	let node = db.get(root, EMPTY_PREFIX)?;
+	let node = Rlp::new(&node);
+	match node.item_count() {
+		Ok(17) => node.at(part).ok().map(|child| child.as_raw().to_vec()),
+		_ => None,
+	}
+}
+
+fn decompress_chunk(compressed: &[u8]) -> Result<Bytes, Error> {
+	snappy::decompress(compressed).map_err(|e| Error::WrongChunkFormat(format!("{}", e)))
+}
+
 /// Used to rebuild the state trie piece by piece.
 pub struct StateRebuilder {
 	db: Box<dyn JournalDB>,
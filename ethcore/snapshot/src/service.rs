@@ -493,10 +493,21 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 	}
 
 	/// Take a snapshot at the block with the given number.
+	/// Unchanged parts of the state are copied over from the current snapshot.
 	/// Calling this while a restoration is in progress or vice versa
 	/// will lead to a race condition where the first one to finish will
 	/// have their produced snapshot overwritten.
 	pub fn take_snapshot(&self, client: &C, num: u64) -> Result<(), Error> {
+		self.take_snapshot_with_progress(client, num, &|_| ())
+	}
+
+	/// Take a snapshot at the block with the given number, like `take_snapshot`,
+	/// calling `on_progress` with the updated progress after every chunk written.
+	///
+	/// `abort_snapshot` cancels it: chunking stops before the next chunk, the chunks produced
+	/// so far are discarded with the temporary snapshot directory, the current snapshot is kept
+	/// and `SnapshotAborted` is returned.
+	pub fn take_snapshot_with_progress(&self, client: &C, num: u64, on_progress: &(dyn Fn(Progress) + Sync)) -> Result<(), Error> {
 		if self.taking_snapshot.compare_and_swap(false, true, Ordering::SeqCst) {
 			info!("Skipping snapshot at #{} as another one is currently in-progress.", num);
 			return Ok(());
@@ -518,7 +529,12 @@ impl<C> Service<C> where C: SnapshotClient + ChainInfo {
 			let writer = LooseWriter::new(temp_dir.clone())?;
 
 			let guard = Guard::new(temp_dir.clone());
-			let _ = client.take_snapshot(writer, BlockId::Number(num), &self.progress)?;
+			{
+				// reuse what we can from the snapshot being replaced.
+				let previous = self.reader.read();
+				let previous = previous.as_ref().map(|reader| reader as &(dyn SnapshotReader + Sync));
+				client.take_snapshot(writer, BlockId::Number(num), &self.progress, previous, on_progress)?;
+			}
 			info!("Finished taking snapshot at #{}, in {:.0?}", num, start_time.elapsed());
 
 			// destroy the old snapshot reader.
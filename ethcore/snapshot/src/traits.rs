@@ -28,7 +28,7 @@ use engine::Engine;
 use ethereum_types::H256;
 use parking_lot::RwLock;
 
-use crate::io::SnapshotWriter;
+use crate::io::{SnapshotReader, SnapshotWriter};
 
 /// The interface for a snapshot network service.
 /// This handles:
@@ -100,7 +100,8 @@ pub trait SnapshotComponents: Send {
 	/// in the state chunks.
 	///
 	/// Chunks shouldn't exceed the given preferred size, and should be fed
-	/// uncompressed into the sink.
+	/// uncompressed into the sink. Once `progress` is flagged to abort, chunking
+	/// should stop and return successfully, keeping the chunks written so far.
 	///
 	/// This will vary by consensus engine, so it's exposed as a trait.
 	fn chunk_all(
@@ -137,11 +138,15 @@ pub trait SnapshotComponents: Send {
 pub trait SnapshotClient: BlockChainClient + BlockInfo + DatabaseRestore + BlockChainReset {
 	/// Take a snapshot at the given block.
 	/// If the BlockId is 'Latest', this will default to 1000 blocks behind.
+	/// Unchanged parts of the state are copied over from the `previous` snapshot, if any.
+	/// `on_progress` is called after every chunk written; see `snapshot::take_snapshot`.
 	fn take_snapshot<W: SnapshotWriter + Send>(
 		&self,
 		writer: W,
 		at: BlockId,
 		p: &RwLock<Progress>,
+		previous: Option<&(dyn SnapshotReader + Sync)>,
+		on_progress: &(dyn Fn(Progress) + Sync),
 	) -> Result<(), Error>;
 }
 
@@ -97,7 +97,7 @@ use machine::{
 };
 use miner::{Miner, MinerService, PendingOrdering};
 use registrar::RegistrarClient;
-use snapshot::{self, SnapshotClient, SnapshotWriter};
+use snapshot::{self, SnapshotClient, SnapshotWriter, io::SnapshotReader};
 use spec::Spec;
 use state_db::StateDB;
 use trace::{self, Database as TraceDatabase, ImportRequest as TraceImportRequest, LocalizedTrace, TraceDB};
@@ -129,14 +129,15 @@ use types::{
 	pruning_info::PruningInfo,
 	receipt::{LocalizedReceipt, Receipt},
 	snapshot::{Progress, Snapshotting},
+	state_override::StateOverride,
 	trace_filter::Filter as TraceFilter,
-	transaction::{self, Action, CallError, LocalizedTransaction, SignedTransaction, UnverifiedTransaction},
+	transaction::{self, Action, CallError, LocalizedTransaction, SignedTransaction, TransactionStatus, UnverifiedTransaction},
 	verification::{Unverified, VerificationQueueInfo as BlockQueueInfo},
 };
 use types::data_format::DataFormat;
 use verification::{self, BlockQueue};
 use verification::queue::kind::BlockLike;
-use vm::{CreateContractAddress, EnvInfo, LastHashes};
+use vm::{CreateContractAddress, EnvInfo, Error as VmError, LastHashes};
 
 const MAX_ANCIENT_BLOCKS_QUEUE_SIZE: usize = 4096;
 // Max number of blocks imported at once.
@@ -235,7 +236,8 @@ pub struct Client {
 	/// Consensus messages import queue
 	queue_consensus_message: IoChannelQueue,
 
-	last_hashes: RwLock<VecDeque<H256>>,
+	/// Hashes of the last 256 blocks, starting with the most recent one we were asked about.
+	last_hashes: RwLock<Arc<LastHashes>>,
 	factories: Factories,
 
 	/// Number of eras kept in a journal before they are pruned
@@ -789,7 +791,7 @@ impl Client {
 			queued_ancient_blocks: Default::default(),
 			ancient_blocks_import_lock: Default::default(),
 			queue_consensus_message: IoChannelQueue::new(usize::max_value()),
-			last_hashes: RwLock::new(VecDeque::new()),
+			last_hashes: RwLock::new(Arc::new(LastHashes::new())),
 			factories,
 			history,
 			on_user_defaults_change: Mutex::new(None),
@@ -910,11 +912,10 @@ impl Client {
 
 	fn build_last_hashes(&self, parent_hash: H256) -> Arc<LastHashes> {
 		{
+			// the ancestry of a hash never changes, so a window starting at it is still valid after a reorg.
 			let hashes = self.last_hashes.read();
-			if hashes.front().map_or(false, |h| h == &parent_hash) {
-				let mut res = Vec::from(hashes.clone());
-				res.resize(256, H256::zero());
-				return Arc::new(res);
+			if hashes.first() == Some(&parent_hash) {
+				return hashes.clone();
 			}
 		}
 		let mut last_hashes = LastHashes::new();
@@ -929,9 +930,9 @@ impl Client {
 				None => break,
 			}
 		}
-		let mut cached_hashes = self.last_hashes.write();
-		*cached_hashes = VecDeque::from(last_hashes.clone());
-		Arc::new(last_hashes)
+		let last_hashes = Arc::new(last_hashes);
+		*self.last_hashes.write() = last_hashes.clone();
+		last_hashes
 	}
 
 	// use a state-proving closure for the given block.
@@ -1005,11 +1006,11 @@ impl Client {
 
 	fn update_last_hashes(&self, parent: &H256, hash: &H256) {
 		let mut hashes = self.last_hashes.write();
-		if hashes.front().map_or(false, |h| h == parent) {
-			if hashes.len() > 255 {
-				hashes.pop_back();
-			}
-			hashes.push_front(hash.clone());
+		if hashes.first() == Some(parent) {
+			let mut shifted = LastHashes::with_capacity(256);
+			shifted.push(*hash);
+			shifted.extend(hashes.iter().take(255).cloned());
+			*hashes = Arc::new(shifted);
 		}
 	}
 
@@ -1209,6 +1210,7 @@ impl Client {
 		state: &mut State<StateDB>,
 		t: &SignedTransaction,
 		analytics: CallAnalytics,
+		deadline: Option<Instant>,
 	) -> Result<Executed, CallError> {
 		use types::engines::machine::Executed as RawExecuted;
 		fn call<V, T>(
@@ -1218,13 +1220,15 @@ impl Client {
 			state_diff: bool,
 			transaction: &SignedTransaction,
 			options: TransactOptions<T, V>,
+			deadline: Option<Instant>,
 		) -> Result<RawExecuted<T::Output, V::Output>, CallError> where
 			T: trace::Tracer,
 			V: trace::VMTracer,
 		{
 			let options = options
 				.dont_check_nonce()
-				.save_output_from_contract();
+				.save_output_from_contract()
+				.with_deadline(deadline);
 			let original_state = if state_diff { Some(state.clone()) } else { None };
 			let schedule = machine.schedule(env_info.number);
 
@@ -1239,10 +1243,35 @@ impl Client {
 		let state_diff = analytics.state_diffing;
 
 		match (analytics.transaction_tracing, analytics.vm_tracing) {
-			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing()),
-			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing()),
-			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing()),
-			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing()),
+			(true, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing_and_vm_tracing(), deadline),
+			(true, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_tracing(), deadline),
+			(false, true) => call(state, env_info, machine, state_diff, t, TransactOptions::with_vm_tracing(), deadline),
+			(false, false) => call(state, env_info, machine, state_diff, t, TransactOptions::with_no_tracing(), deadline),
+		}
+	}
+
+	/// Like `do_virtual_call`, but never lets the call use more than the configured `rpc_gas_cap`.
+	fn do_capped_virtual_call(
+		&self,
+		machine: &::machine::Machine,
+		env_info: &EnvInfo,
+		state: &mut State<StateDB>,
+		t: &SignedTransaction,
+		analytics: CallAnalytics,
+		deadline: Option<Instant>,
+	) -> Result<Executed, CallError> {
+		let cap = self.config.rpc_gas_cap;
+		if t.gas <= cap {
+			return Self::do_virtual_call(machine, env_info, state, t, analytics, deadline);
+		}
+
+		let mut capped = t.as_unsigned().clone();
+		capped.gas = cap;
+		let capped = capped.fake_sign(t.sender());
+		let executed = Self::do_virtual_call(machine, env_info, state, &capped, analytics, deadline)?;
+		match executed.exception {
+			Some(VmError::OutOfGas) => Err(CallError::GasCapExceeded(cap)),
+			_ => Ok(executed),
 		}
 	}
 
@@ -1511,8 +1540,15 @@ impl Call for Client {
 			gas_limit: U256::max_value(),
 		};
 		let machine = self.engine.machine();
+		let deadline = self.config.call_timeout.map(|timeout| Instant::now() + timeout);
+
+		self.do_capped_virtual_call(&machine, &env_info, state, transaction, analytics, deadline)
+	}
 
-		Self::do_virtual_call(&machine, &env_info, state, transaction, analytics)
+	fn call_with_overrides(&self, transaction: &SignedTransaction, analytics: CallAnalytics, overrides: &StateOverride, state: &Self::State, header: &Header) -> Result<Executed, CallError> {
+		let mut state = state.clone();
+		state.apply_override(overrides).map_err(ExecutionError::from)?;
+		self.call(transaction, analytics, &mut state, header)
 	}
 
 	fn call_many(&self, transactions: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
@@ -1528,9 +1564,10 @@ impl Call for Client {
 
 		let mut results = Vec::with_capacity(transactions.len());
 		let machine = self.engine.machine();
+		let deadline = self.config.call_timeout.map(|timeout| Instant::now() + timeout);
 
 		for &(ref t, analytics) in transactions {
-			let ret = Self::do_virtual_call(machine, &env_info, state, t, analytics)?;
+			let ret = self.do_capped_virtual_call(machine, &env_info, state, t, analytics, deadline)?;
 			env_info.gas_used = ret.cumulative_gas_used;
 			results.push(ret);
 		}
@@ -1540,8 +1577,8 @@ impl Call for Client {
 
 	fn estimate_gas(&self, t: &SignedTransaction, state: &Self::State, header: &Header) -> Result<U256, CallError> {
 		let (mut upper, max_upper, env_info) = {
-			let init = *header.gas_limit();
-			let max = init * U256::from(10);
+			let max = cmp::min(*header.gas_limit() * U256::from(10), self.config.rpc_gas_cap);
+			let init = cmp::min(*header.gas_limit(), max);
 
 			let env_info = EnvInfo {
 				number: header.number(),
@@ -1581,8 +1618,11 @@ impl Call for Client {
 			upper = max_upper;
 			match exec(upper) {
 				Ok(v) => {
-					if let Some(exception) = v.exception {
-						return Err(CallError::Exceptional(exception))
+					match v.exception {
+						Some(VmError::OutOfGas) if upper == self.config.rpc_gas_cap =>
+							return Err(CallError::GasCapExceeded(upper)),
+						Some(exception) => return Err(CallError::Exceptional(exception)),
+						None => {},
 					}
 				},
 				Err(_e) => {
@@ -1659,7 +1699,7 @@ impl BlockChainClient for Client {
 				let transaction_hash = t.hash();
 				let t = SignedTransaction::new(t).expect(PROOF);
 				let machine = engine.machine();
-				let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, analytics).expect(EXECUTE_PROOF);
+				let x = Self::do_virtual_call(machine, &env_info, &mut state, &t, analytics, None).expect(EXECUTE_PROOF);
 				env_info.gas_used = env_info.gas_used + x.gas_used;
 				(transaction_hash, x)
 			})))
@@ -1773,6 +1813,10 @@ impl BlockChainClient for Client {
 		}
 	}
 
+	fn account_exists(&self, address: &Address, id: BlockId) -> Option<bool> {
+		self.state_at(id).and_then(|s| s.exists(address).ok())
+	}
+
 	fn list_accounts(&self, id: BlockId, after: Option<&Address>, count: u64) -> Option<Vec<Address>> {
 		if !self.factories.trie.is_fat() {
 			trace!(target: "fatdb", "list_accounts: Not a fat DB");
@@ -1874,6 +1918,19 @@ impl BlockChainClient for Client {
 		self.transaction_address(id).and_then(|address| self.chain.read().transaction(&address))
 	}
 
+	fn transaction_status(&self, hash: &H256) -> TransactionStatus {
+		if let Some(address) = self.chain.read().transaction_address(hash) {
+			return TransactionStatus::Mined { block: address.block_hash, index: address.index };
+		}
+		let miner = &self.importer.miner;
+		match miner.transaction(hash) {
+			// the sender's next nonce counts every consecutive pooled transaction that is ready.
+			Some(tx) if tx.signed().nonce < miner.next_nonce(self, &tx.signed().sender()) => TransactionStatus::Pending,
+			Some(_) => TransactionStatus::Queued,
+			None => TransactionStatus::Unknown,
+		}
+	}
+
 	fn uncle(&self, id: UncleId) -> Option<encoded::Header> {
 		let index = id.position;
 		self.block_body(id.block).and_then(|body| body.view().uncle_rlp_at(index))
@@ -1954,6 +2011,8 @@ impl BlockChainClient for Client {
 	}
 
 	fn logs(&self, filter: Filter) -> Result<Vec<LocalizedLogEntry>, BlockId> {
+		// blocks below the horizon have no receipts left to search.
+		let horizon = self.history_horizon().unwrap_or(0);
 		let chain = self.chain.read();
 
 		// First, check whether `filter.from_block` and `filter.to_block` is on the canon chain. If so, we can use the
@@ -1975,7 +2034,7 @@ impl BlockChainClient for Client {
 			// don't need to care about pending blocks here because RPC query sets pending back to latest (or handled
 			// pending logs themselves).
 			let from = match self.block_number_ref(&filter.from_block) {
-				Some(val) if val <= chain.best_block_number() => val,
+				Some(val) if val >= horizon && val <= chain.best_block_number() => val,
 				_ => return Err(filter.from_block),
 			};
 			let to = match self.block_number_ref(&filter.to_block) {
@@ -2000,8 +2059,8 @@ impl BlockChainClient for Client {
 				None => return Err(filter.from_block),
 			};
 			let from_number = match chain.block_number(&from_hash) {
-				Some(val) => val,
-				None => return Err(BlockId::Hash(from_hash)),
+				Some(val) if val >= horizon => val,
+				_ => return Err(BlockId::Hash(from_hash)),
 			};
 			let to_hash = match Self::block_hash(&chain, filter.to_block) {
 				Some(val) => val,
@@ -2106,8 +2165,8 @@ impl BlockChainClient for Client {
 			.and_then(|number| self.tracedb.read().block_traces(number))
 	}
 
-	fn last_hashes(&self) -> LastHashes {
-		self.build_last_hashes(self.chain.read().best_block_hash()).to_vec()
+	fn last_hashes(&self, parent: &H256) -> Arc<LastHashes> {
+		self.build_last_hashes(*parent)
 	}
 
 	fn transactions_to_propagate(&self) -> Vec<Arc<VerifiedTransaction>> {
@@ -2157,6 +2216,20 @@ impl BlockChainClient for Client {
 		}
 	}
 
+	fn history_size(&self) -> Option<u64> {
+		let chain = self.chain.read();
+		chain.first_block_number().map(|first| chain.best_block_number().saturating_sub(first) + 1)
+	}
+
+	fn block_range(&self, from: BlockNumber, to: BlockNumber) -> Box<dyn Iterator<Item = (BlockNumber, Bytes)> + '_> {
+		let chain = self.chain.read().clone();
+		Box::new((from..=to).filter_map(move |number| {
+			chain.block_hash(number)
+				.and_then(|hash| chain.block(&hash))
+				.map(|block| (number, block.into_inner()))
+		}))
+	}
+
 	fn create_transaction(&self, TransactionRequest { action, data, gas, gas_price, nonce }: TransactionRequest)
 		-> Result<SignedTransaction, transaction::Error>
 	{
@@ -2186,7 +2259,13 @@ impl BlockChainClient for Client {
 
 	fn transact(&self, tx_request: TransactionRequest) -> Result<(), transaction::Error> {
 		let signed = self.create_transaction(tx_request)?;
-		self.importer.miner.import_own_transaction(self, signed.into())
+		self.submit_transaction(signed).map(|_| ())
+	}
+
+	fn submit_transaction(&self, signed: SignedTransaction) -> Result<H256, transaction::Error> {
+		let hash = signed.hash();
+		self.importer.miner.import_own_transaction(self, signed.into())?;
+		Ok(hash)
 	}
 }
 
@@ -2476,13 +2555,16 @@ impl client_traits::EngineClient for Client {
 	}
 
 	fn submit_seal(&self, block_hash: H256, seal: Vec<Bytes>) {
-		let import = self.importer.miner.submit_seal(block_hash, seal)
-			.and_then(|block| self.import_sealed_block(block));
-		if let Err(err) = import {
+		if let Err(err) = self.submit_seal_and_import(block_hash, seal) {
 			warn!(target: "poa", "Wrong internal seal submission! {:?}", err);
 		}
 	}
 
+	fn submit_seal_and_import(&self, block_hash: H256, seal: Vec<Bytes>) -> EthcoreResult<H256> {
+		self.importer.miner.submit_seal(block_hash, seal)
+			.and_then(|block| self.import_sealed_block(block))
+	}
+
 	fn broadcast_consensus_message(&self, message: Bytes) {
 		self.notify(|notify| {
 			notify.broadcast(ChainMessageType::Consensus(message.clone()))
@@ -2547,6 +2629,8 @@ impl SnapshotClient for Client {
 		writer: W,
 		at: BlockId,
 		p: &RwLock<Progress>,
+		previous: Option<&(dyn SnapshotReader + Sync)>,
+		on_progress: &(dyn Fn(Progress) + Sync),
 	) -> Result<(), EthcoreError> {
 		if let Snapshotting::Unsupported = self.engine.snapshot_mode() {
 			return Err(EthcoreError::Snapshot(SnapshotError::SnapshotsUnsupported));
@@ -2603,12 +2687,47 @@ impl SnapshotClient for Client {
 				writer,
 				p,
 				processing_threads,
+				previous,
+				on_progress,
 			)?;
 			Ok(())
 		}
 	}
 }
 
+/// Writes the canonical blocks `from..=to` of `client` to `out` in the given format.
+/// Shared by the `ImportExportBlocks` implementations of the real and the test client.
+pub(crate) fn export_blocks<C: BlockChainClient + ?Sized>(
+	client: &C,
+	out: &mut dyn std::io::Write,
+	from: BlockId,
+	to: BlockId,
+	format: Option<DataFormat>
+) -> Result<(), String> {
+	let from = client.block_number(from).ok_or("Starting block could not be found")?;
+	let to = client.block_number(to).ok_or("End block could not be found")?;
+	let format = format.unwrap_or_default();
+
+	if let Some(horizon) = client.history_horizon().filter(|horizon| from < *horizon) {
+		return Err(format!("Starting block #{} is before the pruning horizon #{}", from, horizon));
+	}
+
+	for i in from..=to {
+		if i % 10000 == 0 {
+			info!("#{}", i);
+		}
+		let b = client.block(BlockId::Number(i))
+			.ok_or("Error exporting incomplete chain")?
+			.into_inner();
+		let written = match format {
+			DataFormat::Binary => out.write_all(&b),
+			DataFormat::Hex => out.write_fmt(format_args!("{}\n", b.pretty())),
+		};
+		written.map_err(|e| format!("Couldn't write to stream. Cause: {}", e))?;
+	}
+	Ok(())
+}
+
 impl ImportExportBlocks for Client {
 	fn export_blocks<'a>(
 		&self,
@@ -2617,33 +2736,7 @@ impl ImportExportBlocks for Client {
 		to: BlockId,
 		format: Option<DataFormat>
 	) -> Result<(), String> {
-		let from = self.block_number(from).ok_or("Starting block could not be found")?;
-		let to = self.block_number(to).ok_or("End block could not be found")?;
-		let format = format.unwrap_or_default();
-
-		for i in from..=to {
-			if i % 10000 == 0 {
-				info!("#{}", i);
-			}
-			let b = self.block(BlockId::Number(i))
-				.ok_or("Error exporting incomplete chain")?
-				.into_inner();
-			match format {
-				DataFormat::Binary => {
-					out.write(&b)
-						.map_err(|e| {
-							format!("Couldn't write to stream. Cause: {}", e)
-						})?;
-				}
-				DataFormat::Hex => {
-					out.write_fmt(format_args!("{}\n", b.pretty()))
-						.map_err(|e| {
-							format!("Couldn't write to stream. Cause: {}", e)
-						})?;
-				}
-			}
-		}
-		Ok(())
+		export_blocks(self, &mut *out, from, to, format)
 	}
 
 	fn import_blocks<'a>(
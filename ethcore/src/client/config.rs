@@ -15,8 +15,10 @@
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
 use std::str::FromStr;
+use std::time::Duration;
 
 use blockchain::Config as BlockChainConfig;
+use ethereum_types::U256;
 use journaldb;
 use snapshot::SnapshotConfiguration;
 use trace::Config as TraceConfig;
@@ -94,6 +96,10 @@ pub struct ClientConfig {
 	pub max_round_blocks_to_import: usize,
 	/// Snapshot configuration
 	pub snapshot: SnapshotConfiguration,
+	/// Maximal time a single `call` may run for before it is aborted. No limit if `None`.
+	pub call_timeout: Option<Duration>,
+	/// Maximal amount of gas a single `call` or `estimate_gas` may use.
+	pub rpc_gas_cap: U256,
 }
 
 impl Default for ClientConfig {
@@ -119,6 +125,8 @@ impl Default for ClientConfig {
 			transaction_verification_queue_size: 8192,
 			max_round_blocks_to_import: 12,
 			snapshot: Default::default(),
+			call_timeout: None,
+			rpc_gas_cap: U256::from(500_000_000),
 		}
 	}
 }
@@ -23,6 +23,7 @@ mod config;
 mod traits;
 
 pub use self::client::Client;
+pub(crate) use self::client::export_blocks;
 pub use self::config::{ClientConfig, DatabaseCompactionProfile};
 pub use self::traits::{
     ReopenBlock, PrepareOpenBlock, ImportSealedBlock, BroadcastProposalBlock,
@@ -19,6 +19,7 @@ use ethereum_types::{H256, U256, Address};
 use types::{
 	transaction::{SignedTransaction, CallError},
 	call_analytics::CallAnalytics,
+	state_override::StateOverride,
 	errors::EthcoreError as Error,
 	errors::EthcoreResult,
 	header::Header,
@@ -37,6 +38,10 @@ pub trait Call {
 	/// Makes a non-persistent transaction call.
 	fn call(&self, tx: &SignedTransaction, analytics: CallAnalytics, state: &mut Self::State, header: &Header) -> Result<Executed, CallError>;
 
+	/// Makes a non-persistent transaction call on a copy of `state` with `overrides` applied.
+	/// Neither the overrides nor the effects of the call are visible in `state` afterwards.
+	fn call_with_overrides(&self, tx: &SignedTransaction, analytics: CallAnalytics, overrides: &StateOverride, state: &Self::State, header: &Header) -> Result<Executed, CallError>;
+
 	/// Makes multiple non-persistent but dependent transaction calls.
 	/// Returns a vector of successes or a failure if any of the transaction fails.
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError>;
@@ -987,39 +987,46 @@ impl miner::MinerService for Miner {
 		&self,
 		chain: &C,
 		transactions: Vec<UnverifiedTransaction>
-	) -> Vec<Result<(), transaction::Error>> {
+	) -> pool::ImportOutcome {
 		trace!(target: "external_tx", "Importing external transactions");
 		let client = self.pool_client(chain);
-		let results = self.transaction_queue.import(
+		let outcome = self.transaction_queue.import_with_outcome(
 			client,
 			transactions.into_iter().map(pool::verifier::Transaction::Unverified),
 		);
+		if !outcome.evicted.is_empty() {
+			debug!(target: "external_tx", "Evicted {} transactions to stay within the pool limits", outcome.evicted.len());
+		}
 
 		// --------------------------------------------------------------------------
 		// | NOTE Code below requires sealing locks.                                |
 		// | Make sure to release the locks before calling that method.             |
 		// --------------------------------------------------------------------------
-		if !results.is_empty() && self.options.reseal_on_external_tx &&	self.sealing.lock().reseal_allowed() {
+		if !outcome.results.is_empty() && self.options.reseal_on_external_tx &&	self.sealing.lock().reseal_allowed() {
 			self.prepare_and_update_sealing(chain);
 		}
 
-		results
+		outcome
 	}
 
 	fn import_own_transaction<C: miner::BlockChainClient>(
 		&self,
 		chain: &C,
 		pending: PendingTransaction
-	) -> Result<(), transaction::Error> {
+	) -> Result<Vec<H256>, transaction::Error> {
 		// note: you may want to use `import_claimed_local_transaction` instead of this one.
 
 		trace!(target: "own_tx", "Importing transaction: {:?}", pending);
 
 		let client = self.pool_client(chain);
-		let imported = self.transaction_queue.import(
+		let pool::ImportOutcome { mut results, evicted } = self.transaction_queue.import_with_outcome(
 			client,
 			Some(pool::verifier::Transaction::Local(pending))
-		).pop().expect("one result returned per added transaction; one added => one result; qed");
+		);
+		let imported = results.pop().expect("one result returned per added transaction; one added => one result; qed");
+		if !evicted.is_empty() {
+			debug!(target: "own_tx", "Evicted {} transactions to stay within the pool limits", evicted.len());
+		}
 
 		// --------------------------------------------------------------------------
 		// | NOTE Code below requires sealing locks.                                |
@@ -1029,7 +1036,7 @@ impl miner::MinerService for Miner {
 			self.prepare_and_update_sealing(chain);
 		}
 
-		imported
+		imported.map(|()| evicted)
 	}
 
 	fn import_claimed_local_transaction<C: miner::BlockChainClient>(
@@ -1037,7 +1044,7 @@ impl miner::MinerService for Miner {
 		chain: &C,
 		pending: PendingTransaction,
 		trusted: bool
-	) -> Result<(), transaction::Error> {
+	) -> Result<Vec<H256>, transaction::Error> {
 		// treat the tx as local if the option is enabled, if we have the account, or if
 		// the account is specified as a Prioritized Local Addresses
 		let sender = pending.sender();
@@ -1050,8 +1057,8 @@ impl miner::MinerService for Miner {
 		} else {
 			// We want to replicate behaviour for external transactions if we're not going to treat
 			// this as local. This is important with regards to sealing blocks
-			self.import_external_transactions(chain, vec![pending.transaction.into()])
-				.pop().expect("one result per tx, as in `import_own_transaction`")
+			let pool::ImportOutcome { mut results, evicted } = self.import_external_transactions(chain, vec![pending.transaction.into()]);
+			results.pop().expect("one result per tx, as in `import_own_transaction`").map(|()| evicted)
 		}
 	}
 
@@ -1591,7 +1598,7 @@ mod tests {
 		let res = miner.import_own_transaction(&client, PendingTransaction::new(transaction, None));
 
 		// then
-		assert_eq!(res.unwrap(), ());
+		assert!(res.unwrap().is_empty());
 		assert_eq!(miner.pending_transactions(best_block).unwrap().len(), 1);
 		assert_eq!(miner.pending_receipts(best_block).unwrap().len(), 1);
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 1);
@@ -1637,12 +1644,59 @@ mod tests {
 		let res = miner.import_own_transaction(&client, PendingTransaction::new(transaction, None));
 
 		// then
-		assert_eq!(res.unwrap(), ());
+		assert!(res.unwrap().is_empty());
 		assert_eq!(miner.pending_transactions(best_block), None);
 		assert_eq!(miner.pending_receipts(best_block), None);
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 1);
 	}
 
+	#[test]
+	fn should_report_transactions_evicted_on_import() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = Miner::new(
+			MinerOptions {
+				pool_limits: pool::Options {
+					max_count: 2,
+					max_per_sender: 1,
+					max_mem_usage: 4 * 1024 * 1024,
+				},
+				..miner().options
+			},
+			GasPricer::new_fixed(0u64.into()),
+			&spec::new_test(),
+			HashSet::new(),
+		);
+		let priced = |gas_price: u64| {
+			let keypair = Random.generate().unwrap();
+			let transaction = Transaction {
+				action: Action::Create,
+				value: U256::zero(),
+				data: "3331600055".from_hex().unwrap(),
+				gas: U256::from(100_000),
+				gas_price: gas_price.into(),
+				nonce: U256::zero(),
+			}.sign(keypair.secret(), Some(TEST_CHAIN_ID));
+			client.set_balance(transaction.sender(), U256::from(1_000_000_000u64));
+			transaction
+		};
+		let cheap = vec![priced(1), priced(2)];
+		let outcome = miner.import_external_transactions(&client, cheap.iter().cloned().map(Into::into).collect());
+		assert_eq!(outcome.results, vec![Ok(()), Ok(())]);
+		assert!(outcome.evicted.is_empty());
+
+		// when
+		let pricey = priced(10);
+		let outcome = miner.import_external_transactions(&client, vec![pricey.clone().into()]);
+		let evicted = miner.import_own_transaction(&client, PendingTransaction::new(priced(5), None));
+
+		// then
+		assert_eq!(outcome.results, vec![Ok(())]);
+		assert_eq!(outcome.evicted, vec![cheap[0].hash()]);
+		assert_eq!(evicted, Ok(vec![cheap[1].hash()]));
+		assert!(miner.transaction(&pricey.hash()).is_some());
+	}
+
 	#[test]
 	fn should_import_external_transaction() {
 		// given
@@ -1651,7 +1705,7 @@ mod tests {
 		let transaction = transaction().into();
 		let best_block = 0;
 		// when
-		let res = miner.import_external_transactions(&client, vec![transaction]).pop().unwrap();
+		let res = miner.import_external_transactions(&client, vec![transaction]).results.pop().unwrap();
 
 		// then
 		assert_eq!(res.unwrap(), ());
@@ -1692,7 +1746,7 @@ mod tests {
 		// then
 		// Check the same conditions as `should_import_external_transaction` first. Behaviour should be identical.
 		// That is: it's treated as though we added it through `import_external_transactions`
-		assert_eq!(res.unwrap(), ());
+		assert!(res.unwrap().is_empty());
 		assert_eq!(miner.pending_transactions(best_block), None);
 		assert_eq!(miner.pending_receipts(best_block), None);
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 0);
@@ -1706,13 +1760,46 @@ mod tests {
 
 		// then - 2nd part: we add on the results from the last pending block.
 		// This is borrowed from `should_make_pending_block_when_importing_own_transaction` and slightly modified.
-		assert_eq!(res2.unwrap(), ());
+		assert!(res2.unwrap().is_empty());
 		assert_eq!(miner.pending_transactions(best_block).unwrap().len(), 2);
 		assert_eq!(miner.pending_receipts(best_block).unwrap().len(), 2);
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 2);
 		assert_eq!(miner.prepare_pending_block(&client), BlockPreparationStatus::NotPrepared);
 	}
 
+	#[test]
+	fn should_return_result_per_external_transaction() {
+		// given
+		let client = TestBlockChainClient::default();
+		let miner = miner();
+		let valid = transaction();
+		let wrong_chain = transaction_with_chain_id(TEST_CHAIN_ID + 1);
+		let stale = transaction();
+		client.set_nonce(stale.sender(), 1.into());
+		let unfunded = Transaction {
+			action: Action::Create,
+			value: U256::from(1),
+			data: Vec::new(),
+			gas: U256::from(100_000),
+			gas_price: U256::zero(),
+			nonce: U256::zero(),
+		}.sign(Random.generate().unwrap().secret(), Some(TEST_CHAIN_ID));
+
+		// when
+		let results = miner.import_external_transactions(
+			&client,
+			vec![valid.into(), wrong_chain.into(), stale.into(), unfunded.into()],
+		).results;
+
+		// then
+		assert_eq!(results, vec![
+			Ok(()),
+			Err(transaction::Error::InvalidChainId),
+			Err(transaction::Error::Old),
+			Err(transaction::Error::InsufficientBalance { cost: 1.into(), balance: 0.into() }),
+		]);
+	}
+
 	#[test]
 	fn should_reject_local_transaction_with_invalid_chain_id() {
 		let spec = spec::new_test();
@@ -1734,7 +1821,7 @@ mod tests {
 			PendingTransaction::new(transaction_with_chain_id(chain_id), None),
 			false,
 		);
-		assert_eq!(import, Ok(()));
+		assert_eq!(import, Ok(vec![]));
 	}
 
 	#[test]
@@ -1756,7 +1843,7 @@ mod tests {
 		let res2 = miner.import_claimed_local_transaction(&client, PendingTransaction::new(transaction, None), false);
 
 		// check to make sure the prioritized transaction is pending
-		assert_eq!(res2.unwrap(), ());
+		assert!(res2.unwrap().is_empty());
 		assert_eq!(miner.pending_transactions(best_block).unwrap().len(), 1);
 		assert_eq!(miner.pending_receipts(best_block).unwrap().len(), 1);
 		assert_eq!(miner.ready_transactions(&client, 10, PendingOrdering::Priority).len(), 1);
@@ -1770,7 +1857,7 @@ mod tests {
 		// By default resealing is not required.
 		assert!(!miner.requires_reseal(1u8.into()));
 
-		miner.import_external_transactions(&client, vec![transaction().into()]).pop().unwrap().unwrap();
+		miner.import_external_transactions(&client, vec![transaction().into()]).results.pop().unwrap().unwrap();
 		assert_eq!(miner.prepare_pending_block(&client), BlockPreparationStatus::Succeeded);
 		// Unless asked to prepare work.
 		assert!(miner.requires_reseal(1u8.into()));
@@ -1787,7 +1874,7 @@ mod tests {
 		let import = miner.import_external_transactions(
 			&*client,
 			vec![transaction_with_chain_id(spec.chain_id()).into()]
-		).pop().unwrap();
+		).results.pop().unwrap();
 		assert_eq!(import.unwrap(), ());
 
 		miner.update_sealing(&*client, ForceUpdateSealing::No);
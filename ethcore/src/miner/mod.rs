@@ -34,7 +34,7 @@ use std::sync::Arc;
 use std::collections::{BTreeSet, BTreeMap};
 
 use bytes::Bytes;
-use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, local_transactions};
+use ethcore_miner::pool::{VerifiedTransaction, QueueStatus, ImportOutcome, local_transactions};
 use ethereum_types::{H256, U256, Address};
 use types::transaction::{self, UnverifiedTransaction, SignedTransaction, PendingTransaction};
 use types::{
@@ -140,19 +140,22 @@ pub trait MinerService : Send + Sync {
 	// Transaction Pool
 
 	/// Imports transactions to transaction queue.
+	/// Returns the result of every transaction and the hashes of pooled transactions evicted to make room for them.
 	fn import_external_transactions<C>(&self, client: &C, transactions: Vec<UnverifiedTransaction>)
-		-> Vec<Result<(), transaction::Error>>
+		-> ImportOutcome
 		where C: BlockChainClient;
 
 	/// Imports own (node owner) transaction to queue.
+	/// Returns the hashes of pooled transactions evicted to make room for it.
 	fn import_own_transaction<C>(&self, chain: &C, transaction: PendingTransaction)
-		-> Result<(), transaction::Error>
+		-> Result<Vec<H256>, transaction::Error>
 		where C: BlockChainClient;
 
 	/// Imports transactions from potentially external sources, with behaviour determined
 	/// by the config flag `tx_queue_allow_unfamiliar_locals`
+	/// Returns the hashes of pooled transactions evicted to make room for it.
 	fn import_claimed_local_transaction<C>(&self, chain: &C, transaction: PendingTransaction, trusted: bool)
-		-> Result<(), transaction::Error>
+		-> Result<Vec<H256>, transaction::Error>
 		where C: BlockChainClient;
 
 	/// Removes transaction from the pool.
@@ -16,6 +16,7 @@
 
 //! Test client.
 
+use std::io::{BufRead, BufReader};
 use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrder};
 use std::sync::Arc;
@@ -44,7 +45,7 @@ use types::{
 	ids::{BlockId, TransactionId, UncleId, TraceId},
 	basic_account::BasicAccount,
 	errors::{EthcoreError as Error, EthcoreResult},
-	transaction::{self, Transaction, LocalizedTransaction, SignedTransaction, Action, CallError},
+	transaction::{self, Transaction, LocalizedTransaction, SignedTransaction, TransactionStatus, Action, CallError},
 	filter::Filter,
 	trace_filter::Filter as TraceFilter,
 	call_analytics::CallAnalytics,
@@ -52,12 +53,14 @@ use types::{
 	log_entry::LocalizedLogEntry,
 	pruning_info::PruningInfo,
 	receipt::{Receipt, LocalizedReceipt, TransactionOutcome},
+	state_override::StateOverride,
 	view,
 	views::BlockView,
 	verification::Unverified,
 	client_types::{Mode, StateResult},
 	blockchain_info::BlockChainInfo,
 	block_status::BlockStatus,
+	data_format::DataFormat,
 	verification::VerificationQueueInfo as BlockQueueInfo,
 };
 use vm::{Schedule, LastHashes};
@@ -72,7 +75,7 @@ use client::{
 use client_traits::{
 	BlockInfo, Nonce, Balance, ChainInfo, TransactionInfo, BlockChainClient, ImportBlock,
 	AccountData, BlockChain, IoClient, BadBlocks, ScheduleInfo, StateClient, ProvingBlockChainClient,
-	StateOrBlock, ForceUpdateSealing, TransactionRequest
+	StateOrBlock, ForceUpdateSealing, TransactionRequest, ImportExportBlocks
 };
 use engine::Engine;
 use machine::executed::Executed;
@@ -129,6 +132,8 @@ pub struct TestBlockChainClient {
 	pub traces: RwLock<Option<Vec<LocalizedTrace>>>,
 	/// Pruning history size to report.
 	pub history: RwLock<Option<u64>>,
+	/// Number of most recent blocks whose full data is retained.
+	pub pruning_window: RwLock<Option<u64>>,
 	/// Is disabled
 	pub disabled: AtomicBool,
 }
@@ -198,6 +203,7 @@ impl TestBlockChainClient {
 			first_block: RwLock::new(None),
 			traces: RwLock::new(None),
 			history: RwLock::new(None),
+			pruning_window: RwLock::new(None),
 			disabled: AtomicBool::new(false),
 			error_on_logs: RwLock::new(None),
 		};
@@ -346,6 +352,41 @@ impl TestBlockChainClient {
 		self.blocks.write().insert(hash, rlp.out());
 	}
 
+	/// Route between two blocks, at least one of which is not canonical.
+	fn fork_route(&self, from: &H256, to: &H256) -> Option<TreeRoute> {
+		let header = |hash: &H256| self.blocks.read().get(hash).map(|b| view!(BlockView, b).header());
+
+		let mut from_header = header(from)?;
+		let mut to_header = header(to)?;
+		let mut from_branch = Vec::new();
+		let mut to_branch = Vec::new();
+
+		while from_header.number() > to_header.number() {
+			from_branch.push(from_header.hash());
+			from_header = header(from_header.parent_hash())?;
+		}
+		while to_header.number() > from_header.number() {
+			to_branch.push(to_header.hash());
+			to_header = header(to_header.parent_hash())?;
+		}
+		while from_header.hash() != to_header.hash() {
+			from_branch.push(from_header.hash());
+			from_header = header(from_header.parent_hash())?;
+			to_branch.push(to_header.hash());
+			to_header = header(to_header.parent_hash())?;
+		}
+
+		let index = from_branch.len();
+		from_branch.extend(to_branch.into_iter().rev());
+
+		Some(TreeRoute {
+			blocks: from_branch,
+			ancestor: from_header.hash(),
+			index,
+			is_from_route_finalized: false,
+		})
+	}
+
 	/// Get block hash with `delta` as offset from the most recent blocks.
 	pub fn block_hash_delta_minus(&mut self, delta: usize) -> H256 {
 		let blocks_read = self.numbers.read();
@@ -376,7 +417,7 @@ impl TestBlockChainClient {
 		let signed_tx = tx.sign(keypair.secret(), None);
 		self.set_balance(signed_tx.sender(), 10_000_000_000_000_000_000u64.into());
 		let hash = signed_tx.hash();
-		let res = self.miner.import_external_transactions(self, vec![signed_tx.into()]);
+		let res = self.miner.import_external_transactions(self, vec![signed_tx.into()]).results;
 		let res = res.into_iter().next().unwrap();
 		assert!(res.is_ok());
 		hash
@@ -392,6 +433,11 @@ impl TestBlockChainClient {
 		*self.history.write() = h;
 	}
 
+	/// Set the number of most recent blocks whose full data is retained.
+	pub fn set_pruning_window(&self, w: Option<u64>) {
+		*self.pruning_window.write() = w;
+	}
+
 	/// Returns true if the client has been disabled.
 	pub fn is_disabled(&self) -> bool {
 		self.disabled.load(AtomicOrder::Relaxed)
@@ -608,6 +654,64 @@ impl ImportBlock for TestBlockChainClient {
 	}
 }
 
+impl ImportExportBlocks for TestBlockChainClient {
+	fn export_blocks<'a>(
+		&self,
+		mut out: Box<dyn std::io::Write + 'a>,
+		from: BlockId,
+		to: BlockId,
+		format: Option<DataFormat>
+	) -> Result<(), String> {
+		client::export_blocks(self, &mut *out, from, to, format)
+	}
+
+	fn import_blocks<'a>(
+		&self,
+		mut source: Box<dyn std::io::Read + 'a>,
+		format: Option<DataFormat>
+	) -> Result<(), String> {
+		let mut blocks = Vec::new();
+		match format.unwrap_or_default() {
+			DataFormat::Binary => {
+				let mut data = Vec::new();
+				source.read_to_end(&mut data)
+					.map_err(|err| format!("Error reading from the file/stream: {:?}", err))?;
+				let mut rest = &data[..];
+				while !rest.is_empty() {
+					let len = Rlp::new(rest).payload_info()
+						.map_err(|e| format!("Invalid RLP in the file/stream: {:?}", e))?
+						.total();
+					if len > rest.len() {
+						return Err("Truncated block in the file/stream".into());
+					}
+					blocks.push(rest[..len].to_vec());
+					rest = &rest[len..];
+				}
+			}
+			DataFormat::Hex => {
+				for line in BufReader::new(source).lines() {
+					let line = line
+						.map_err(|err| format!("Error reading from the file/stream: {:?}", err))?;
+					let bytes = line.from_hex()
+						.map_err(|err| format!("Invalid hex in file/stream: {:?}", err))?;
+					blocks.push(bytes);
+				}
+			}
+		}
+
+		for bytes in blocks {
+			let block = Unverified::from_rlp(bytes).map_err(|_| "Invalid block rlp")?;
+			let number = block.header.number();
+			if number as usize > self.numbers.read().len() {
+				return Err(format!("Cannot import block #{}: missing preceding blocks", number));
+			}
+			self.import_block(block)
+				.map_err(|e| format!("Cannot import block #{}: {:?}", number, e))?;
+		}
+		Ok(())
+	}
+}
+
 impl Call for TestBlockChainClient {
 	// State will not be used by test client anyway, since all methods that accept state are mocked
 	type State = TestState;
@@ -616,6 +720,10 @@ impl Call for TestBlockChainClient {
 		self.execution_result.read().clone().unwrap()
 	}
 
+	fn call_with_overrides(&self, _t: &SignedTransaction, _analytics: CallAnalytics, _overrides: &StateOverride, _state: &Self::State, _header: &Header) -> Result<Executed, CallError> {
+		self.execution_result.read().clone().unwrap()
+	}
+
 	fn call_many(&self, txs: &[(SignedTransaction, CallAnalytics)], state: &mut Self::State, header: &Header) -> Result<Vec<Executed>, CallError> {
 		let mut res = Vec::with_capacity(txs.len());
 		for &(ref tx, analytics) in txs {
@@ -625,7 +733,11 @@ impl Call for TestBlockChainClient {
 	}
 
 	fn estimate_gas(&self, _t: &SignedTransaction, _state: &Self::State, _header: &Header) -> Result<U256, CallError> {
-		Ok(21000.into())
+		match self.execution_result.read().clone() {
+			Some(Ok(Executed { exception: Some(exception), .. })) => Err(CallError::Exceptional(exception)),
+			Some(Err(err)) => Err(err),
+			_ => Ok(21000.into()),
+		}
 	}
 }
 
@@ -727,12 +839,33 @@ impl BlockChainClient for TestBlockChainClient {
 		}
 	}
 
+	fn account_exists(&self, address: &Address, id: BlockId) -> Option<bool> {
+		match id {
+			BlockId::Latest => Some(
+				self.balances.read().contains_key(address) ||
+				self.nonces.read().contains_key(address) ||
+				self.code.read().contains_key(address)
+			),
+			_ => None,
+		}
+	}
+
 	fn chain(&self) -> Arc<dyn BlockProvider> {
 		unimplemented!()
 	}
 
-	fn list_accounts(&self, _id: BlockId, _after: Option<&Address>, _count: u64) -> Option<Vec<Address>> {
-		None
+	fn list_accounts(&self, id: BlockId, after: Option<&Address>, count: u64) -> Option<Vec<Address>> {
+		match id {
+			BlockId::Latest => {
+				let mut accounts: Vec<_> = self.balances.read().keys().cloned().collect();
+				accounts.sort();
+				Some(accounts.into_iter()
+					.filter(|address| after.map_or(true, |after| address > after))
+					.take(count as usize)
+					.collect())
+			}
+			_ => None,
+		}
 	}
 
 	fn list_storage(&self, _id: BlockId, _account: &Address, _after: Option<&H256>, _count: Option<u64>) -> Option<Vec<H256>> {
@@ -742,8 +875,18 @@ impl BlockChainClient for TestBlockChainClient {
 		None	// Simple default.
 	}
 
-	fn uncle(&self, _id: UncleId) -> Option<encoded::Header> {
-		None	// Simple default.
+	fn transaction_status(&self, hash: &H256) -> TransactionStatus {
+		match self.miner.transaction(hash) {
+			Some(tx) if tx.signed().nonce < self.miner.next_nonce(self, &tx.signed().sender()) => TransactionStatus::Pending,
+			Some(_) => TransactionStatus::Queued,
+			None => TransactionStatus::Unknown,
+		}
+	}
+
+	fn uncle(&self, id: UncleId) -> Option<encoded::Header> {
+		self.block_body(id.block)
+			.and_then(|body| body.view().uncle_rlp_at(id.position))
+			.map(encoded::Header::new)
 	}
 
 	fn uncle_extra_info(&self, _id: UncleId) -> Option<BTreeMap<String, String>> {
@@ -765,6 +908,15 @@ impl BlockChainClient for TestBlockChainClient {
 		}
 
 		let mut logs = self.logs.read().clone();
+		if let BlockId::Number(from) = filter.from_block {
+			if self.history_horizon().map_or(false, |horizon| from < horizon) {
+				return Err(filter.from_block);
+			}
+			logs.retain(|log| log.block_number >= from);
+		}
+		if let BlockId::Number(to) = filter.to_block {
+			logs.retain(|log| log.block_number <= to);
+		}
 		let len = logs.len();
 		Ok(match filter.limit {
 			Some(limit) if limit <= len => logs.split_off(len - limit),
@@ -772,7 +924,7 @@ impl BlockChainClient for TestBlockChainClient {
 		})
 	}
 
-	fn last_hashes(&self) -> LastHashes {
+	fn last_hashes(&self, _parent: &H256) -> Arc<LastHashes> {
 		unimplemented!();
 	}
 
@@ -813,6 +965,14 @@ impl BlockChainClient for TestBlockChainClient {
 
 	// works only if blocks are one after another 1 -> 2 -> 3
 	fn tree_route(&self, from: &H256, to: &H256) -> Option<TreeRoute> {
+		{
+			let numbers = self.numbers.read();
+			let is_canonical = |hash: &H256| numbers.values().any(|h| h == hash);
+			if !is_canonical(from) || !is_canonical(to) {
+				return self.fork_route(from, to);
+			}
+		}
+
 		Some(TreeRoute {
 			ancestor: H256::zero(),
 			index: 0,
@@ -907,11 +1067,15 @@ impl BlockChainClient for TestBlockChainClient {
 	fn pruning_info(&self) -> PruningInfo {
 		let best_num = self.chain_info().best_block_number;
 		PruningInfo {
-			earliest_chain: 1,
+			earliest_chain: self.history_horizon().unwrap_or(1),
 			earliest_state: self.history.read().as_ref().map(|x| best_num - x).unwrap_or(0),
 		}
 	}
 
+	fn history_size(&self) -> Option<u64> {
+		*self.pruning_window.read()
+	}
+
 	fn create_transaction(&self, TransactionRequest { action, data, gas, gas_price, nonce }: TransactionRequest)
 		-> Result<SignedTransaction, transaction::Error>
 	{
@@ -930,7 +1094,13 @@ impl BlockChainClient for TestBlockChainClient {
 
 	fn transact(&self, tx_request: TransactionRequest) -> Result<(), transaction::Error> {
 		let signed = self.create_transaction(tx_request)?;
-		self.miner.import_own_transaction(self, signed.into())
+		self.submit_transaction(signed).map(|_| ())
+	}
+
+	fn submit_transaction(&self, signed: SignedTransaction) -> Result<H256, transaction::Error> {
+		let hash = signed.hash();
+		self.miner.import_own_transaction(self, signed.into())?;
+		Ok(hash)
 	}
 }
 
@@ -974,12 +1144,15 @@ impl client_traits::EngineClient for TestBlockChainClient {
 	}
 
 	fn submit_seal(&self, block_hash: H256, seal: Vec<Bytes>) {
-		let import = self.miner.submit_seal(block_hash, seal).and_then(|block| self.import_sealed_block(block));
-		if let Err(err) = import {
+		if let Err(err) = self.submit_seal_and_import(block_hash, seal) {
 			warn!(target: "poa", "Wrong internal seal submission! {:?}", err);
 		}
 	}
 
+	fn submit_seal_and_import(&self, block_hash: H256, seal: Vec<Bytes>) -> EthcoreResult<H256> {
+		self.miner.submit_seal(block_hash, seal).and_then(|block| self.import_sealed_block(block))
+	}
+
 	fn broadcast_consensus_message(&self, _message: Bytes) {}
 
 	fn epoch_transition_for(&self, _block_hash: H256) -> Option<EpochTransition> {
@@ -18,24 +18,27 @@ use std::str::{FromStr, from_utf8};
 use std::sync::Arc;
 
 use account_state::state::StateInfo;
-use ethereum_types::{U256, Address};
+use ethereum_types::{H256, U256, Address};
 use parity_crypto::publickey::KeyPair;
-use hash::keccak;
+use hash::{keccak, KECCAK_NULL_RLP};
 use io::IoChannel;
 use tempdir::TempDir;
 use types::{
 	data_format::DataFormat,
-	ids::BlockId,
-	transaction::{PendingTransaction, Transaction, Action, Condition},
+	ids::{BlockId, UncleId},
+	pruning_info::StateAvailability,
+	transaction::{self, PendingTransaction, Transaction, TransactionStatus, Action, CallError, Condition},
 	filter::Filter,
+	header::Header,
+	state_override::{AccountOverride, StateOverride},
 	verification::Unverified,
 	view,
 	views::BlockView,
 };
 
-use client::{Client, ClientConfig, PrepareOpenBlock, ImportSealedBlock};
+use client::{Call, Client, ClientConfig, PrepareOpenBlock, ImportSealedBlock};
 use client_traits::{
-	BlockInfo, BlockChainClient, BlockChainReset, ChainInfo,
+	Balance, BlockInfo, BlockChainClient, BlockChainReset, BlockSummary, ChainInfo,
 	ImportExportBlocks, Tick, ImportBlock
 };
 use spec;
@@ -46,9 +49,11 @@ use account_state::{State, CleanupMode, backend};
 use test_helpers::{
 	self,
 	generate_dummy_client, push_blocks_to_client, get_test_client_with_blocks, get_good_dummy_block_seq,
-	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block
+	generate_dummy_client_with_data, get_good_dummy_block, get_bad_state_dummy_block,
+	TestBlockChainClient, EachBlockWith,
 };
-use rustc_hex::ToHex;
+use rustc_hex::{FromHex, ToHex};
+use rlp::{RlpStream, NULL_RLP};
 use registrar::RegistrarClient;
 
 #[test]
@@ -195,6 +200,28 @@ fn imports_block_sequence() {
 	assert!(!block.into_inner().is_empty());
 }
 
+#[test]
+fn last_hashes_are_ordered_from_parent() {
+	let client = generate_dummy_client(10);
+	let hash = |n| client.block_hash(BlockId::Number(n)).unwrap();
+	let expected: Vec<_> = (0..=10).rev().map(hash).collect();
+
+	let best = client.chain_info().best_block_hash;
+	let hashes = client.last_hashes(&best);
+	assert_eq!(hashes.len(), 256);
+	assert_eq!(&hashes[..11], &expected[..]);
+	assert!(hashes[11..].iter().all(|h| h.is_zero()));
+	// served from the cache the second time
+	assert!(Arc::ptr_eq(&client.last_hashes(&best), &hashes));
+
+	// a different parent, as after a reorg, rebuilds the window from that block.
+	let older = client.last_hashes(&hash(5));
+	assert_eq!(&older[..6], &expected[5..]);
+	assert!(older[6..].iter().all(|h| h.is_zero()));
+	assert!(!Arc::ptr_eq(&client.last_hashes(&best), &hashes));
+	assert_eq!(client.last_hashes(&best), hashes);
+}
+
 #[test]
 fn can_collect_garbage() {
 	let client = generate_dummy_client(100);
@@ -211,6 +238,23 @@ fn can_generate_gas_price_median() {
 	assert_eq!(Some(&U256::from(3)), client.gas_price_corpus(3).median());
 }
 
+#[test]
+fn can_generate_gas_price_percentiles() {
+	let client = generate_dummy_client_with_data(10, 1, slice_into![5, 1, 9, 3, 7, 2, 8, 4, 10, 6]);
+	let expected: Vec<U256> = vec_into![1, 1, 5, 9, 10];
+	assert_eq!(client.gas_price_percentiles(10, &[0, 10, 50, 90, 100]), expected);
+	assert!(generate_dummy_client_with_data(3, 0, slice_into![]).gas_price_percentiles(3, &[50]).is_empty());
+}
+
+#[test]
+fn gas_price_percentiles_skip_empty_blocks() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(2, EachBlockWith::Transaction);
+	client.add_blocks(5, EachBlockWith::Nothing);
+
+	assert_eq!(client.gas_price_percentiles(3, &[50]), vec![U256::from(200_000_000_000u64)]);
+}
+
 #[test]
 fn can_generate_gas_price_histogram() {
 	let client = generate_dummy_client_with_data(20, 1, slice_into![6354,8593,6065,4842,7845,7002,689,4958,4250,6098,5804,4320,643,8895,2296,8589,7145,2000,2512,1408]);
@@ -468,3 +512,492 @@ fn import_export_binary() {
 	assert!(client.block_header(BlockId::Number(17)).is_some());
 	assert!(client.block_header(BlockId::Number(16)).is_some());
 }
+
+#[test]
+fn import_export_test_client_round_trip() {
+	let source = TestBlockChainClient::new();
+	source.add_blocks(10, EachBlockWith::Transaction);
+
+	let mut out = Vec::new();
+	source.export_blocks(
+		Box::new(&mut out),
+		BlockId::Number(1),
+		BlockId::Latest,
+		Some(DataFormat::Binary)
+	).unwrap();
+
+	let target = TestBlockChainClient::new();
+	target.import_blocks(Box::new(&*out), Some(DataFormat::Binary)).unwrap();
+
+	assert_eq!(target.chain_info().best_block_number, 10);
+	for n in 0..=10 {
+		assert_eq!(
+			target.block_hash(BlockId::Number(n)),
+			source.block_hash(BlockId::Number(n)),
+		);
+	}
+}
+
+#[test]
+fn block_range_yields_blocks_in_order() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(10, EachBlockWith::Transaction);
+
+	let blocks: Vec<_> = client.block_range(2, 15).collect();
+	assert_eq!(blocks.iter().map(|&(n, _)| n).collect::<Vec<_>>(), (2..=10).collect::<Vec<_>>());
+	for (number, rlp) in blocks {
+		assert_eq!(rlp, client.block(BlockId::Number(number)).unwrap().into_inner());
+	}
+}
+
+#[test]
+fn export_missing_blocks_fails() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(5, EachBlockWith::Nothing);
+
+	let res = client.export_blocks(
+		Box::new(Vec::new()),
+		BlockId::Number(1),
+		BlockId::Number(8),
+		Some(DataFormat::Binary)
+	);
+	assert!(res.is_err());
+}
+
+#[test]
+fn queries_before_pruning_horizon_fail() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(10, EachBlockWith::Nothing);
+	assert_eq!(client.history_size(), None);
+
+	client.set_pruning_window(Some(4));
+	assert_eq!(client.history_size(), Some(4));
+	assert_eq!(client.pruning_info().earliest_chain, 7);
+
+	let res = client.export_blocks(
+		Box::new(Vec::new()),
+		BlockId::Number(6),
+		BlockId::Latest,
+		Some(DataFormat::Binary)
+	);
+	assert_eq!(res, Err("Starting block #6 is before the pruning horizon #7".into()));
+	assert_eq!(client.history_horizon(), Some(7));
+
+	let logs_from = |number| client.logs(Filter {
+		from_block: BlockId::Number(number),
+		to_block: BlockId::Latest,
+		address: None,
+		topics: vec![],
+		limit: None,
+	});
+	assert_eq!(logs_from(6), Err(BlockId::Number(6)));
+	assert!(logs_from(7).is_ok());
+
+	let res = client.export_blocks(
+		Box::new(Vec::new()),
+		BlockId::Number(7),
+		BlockId::Latest,
+		Some(DataFormat::Binary)
+	);
+	assert!(res.is_ok());
+}
+
+#[test]
+fn import_with_gap_fails() {
+	let source = TestBlockChainClient::new();
+	source.add_blocks(6, EachBlockWith::Nothing);
+
+	let mut out = Vec::new();
+	source.export_blocks(
+		Box::new(&mut out),
+		BlockId::Number(3),
+		BlockId::Number(6),
+		Some(DataFormat::Hex)
+	).unwrap();
+
+	let target = TestBlockChainClient::new();
+	assert!(target.import_blocks(Box::new(&*out), Some(DataFormat::Hex)).is_err());
+	assert_eq!(target.chain_info().best_block_number, 0);
+}
+
+#[test]
+fn tree_route_between_forks() {
+	// A1 -> A2 -> A3 -> A4
+	//          -> B3 -> B4
+	let client = TestBlockChainClient::new();
+	client.add_blocks(4, EachBlockWith::Nothing);
+	let a = |n| client.block_hash(BlockId::Number(n)).unwrap();
+
+	let fork_block = |parent: H256, number: u64| {
+		let mut header = Header::new();
+		header.set_parent_hash(parent);
+		header.set_number(number);
+		header.set_gas_limit(U256::from(1_000_000));
+		header.set_extra_data(b"fork".to_vec());
+		let mut rlp = RlpStream::new_list(3);
+		rlp.append(&header);
+		rlp.append_raw(&::rlp::EMPTY_LIST_RLP, 1);
+		rlp.append_raw(&::rlp::EMPTY_LIST_RLP, 1);
+		client.import_block(Unverified::from_rlp(rlp.out()).unwrap()).unwrap()
+	};
+	let b3 = fork_block(a(2), 3);
+	let b4 = fork_block(b3, 4);
+
+	let route = client.tree_route(&b4, &a(4)).unwrap();
+	assert_eq!(route.common(), &a(2));
+	assert!(route.is_reorg());
+	assert_eq!(route.retracted(), &[b4, b3][..]);
+	assert_eq!(route.enacted(), &[a(3), a(4)][..]);
+
+	let route = client.tree_route(&a(4), &b4).unwrap();
+	assert_eq!(route.common(), &a(2));
+	assert_eq!(route.retracted(), &[a(4), a(3)][..]);
+	assert_eq!(route.enacted(), &[b3, b4][..]);
+}
+
+#[test]
+fn call_with_overrides_does_not_touch_state() {
+	let client = generate_dummy_client(0);
+	let (state, header) = client.latest_state_and_header();
+
+	// CALLER BALANCE PUSH1 0 MSTORE PUSH1 32 PUSH1 0 RETURN
+	let code: Vec<u8> = "333160005260206000f3".from_hex().unwrap();
+	let contract = Address::from_low_u64_be(0xc0de);
+	let sender = Address::from_low_u64_be(0x5e4d);
+	let balance = U256::from(1_000_000_000u64);
+
+	let mut overrides = StateOverride::new();
+	overrides.insert(contract, AccountOverride { code: Some(code), ..Default::default() });
+	overrides.insert(sender, AccountOverride { balance: Some(balance), ..Default::default() });
+
+	let tx = Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: U256::from(100_000),
+		action: Action::Call(contract),
+		value: U256::zero(),
+		data: vec![],
+	}.fake_sign(sender);
+
+	let executed = client.call_with_overrides(&tx, Default::default(), &overrides, &state, &header).unwrap();
+	let mut expected = [0u8; 32];
+	balance.to_big_endian(&mut expected);
+	assert_eq!(executed.output, expected.to_vec());
+
+	assert_eq!(state.balance(&sender).unwrap(), U256::zero());
+	assert!(state.code(&contract).unwrap().is_none());
+	let executed = client.call(&tx, Default::default(), &mut state.clone(), &header).unwrap();
+	assert!(executed.output.is_empty());
+}
+
+#[test]
+fn list_accounts_paginates_funded_accounts() {
+	let client = TestBlockChainClient::new();
+	let mut expected: Vec<_> = (1..6u64).map(Address::from_low_u64_be).collect();
+	for (i, address) in expected.iter().enumerate() {
+		client.set_balance(*address, U256::from(i + 1));
+	}
+	expected.sort();
+
+	let first = client.list_accounts(BlockId::Latest, None, 2).unwrap();
+	let second = client.list_accounts(BlockId::Latest, first.last(), 2).unwrap();
+	let third = client.list_accounts(BlockId::Latest, second.last(), 2).unwrap();
+	assert_eq!(first.len(), 2);
+	assert_eq!(second.len(), 2);
+	assert_eq!(third.len(), 1);
+
+	let all: Vec<_> = first.into_iter().chain(second).chain(third).collect();
+	assert_eq!(all, expected);
+	assert!(client.list_accounts(BlockId::Latest, all.last(), 2).unwrap().is_empty());
+	assert!(client.list_accounts(BlockId::Number(0), None, 2).is_none());
+}
+
+#[test]
+fn submit_transaction_returns_hash_and_queues_it() {
+	let client = TestBlockChainClient::new();
+	let key = KeyPair::from_secret(keccak("test").into()).unwrap();
+	client.set_balance(key.address(), 1_000_000.into());
+
+	let signed = Transaction {
+		nonce: 0.into(),
+		gas_price: 0.into(),
+		gas: 21000.into(),
+		action: Action::Call(Address::zero()),
+		value: 5.into(),
+		data: Vec::new(),
+	}.sign(key.secret(), None);
+	let expected = signed.hash();
+
+	assert_eq!(client.submit_transaction(signed.clone()), Ok(expected));
+	assert!(client.miner.transaction(&expected).is_some());
+	assert_eq!(client.submit_transaction(signed), Err(transaction::Error::AlreadyImported));
+}
+
+#[test]
+fn estimate_gas_finds_minimal_gas() {
+	let client = generate_dummy_client(0);
+	let (mut state, header) = client.latest_state_and_header();
+
+	let contract = Address::from_low_u64_be(0xc0de);
+	let reverting = Address::from_low_u64_be(0xdead);
+	// PUSH1 1 PUSH1 0 SSTORE
+	state.init_code(&contract, "6001600055".from_hex().unwrap()).unwrap();
+	// INVALID
+	state.init_code(&reverting, "fe".from_hex().unwrap()).unwrap();
+
+	let sender = Address::from_low_u64_be(0x5e4d);
+	let tx = |to: Address, gas: U256| Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas,
+		action: Action::Call(to),
+		value: U256::zero(),
+		data: vec![],
+	}.fake_sign(sender);
+
+	// plain transfer succeeds at intrinsic gas.
+	let transfer = tx(Address::from_low_u64_be(0xf00), 0.into());
+	assert_eq!(client.estimate_gas(&transfer, &state, &header).unwrap(), U256::from(21_000));
+
+	// a contract call needs more, and nothing less than the estimate succeeds.
+	let estimate = client.estimate_gas(&tx(contract, 0.into()), &state, &header).unwrap();
+	assert!(estimate > U256::from(21_000));
+	let succeeds = |gas: U256| client.call(&tx(contract, gas), Default::default(), &mut state.clone(), &header)
+		.map_or(false, |executed| executed.exception.is_none());
+	assert!(succeeds(estimate));
+	assert!(!succeeds(estimate - 1));
+
+	// a call that always fails has no estimate.
+	match client.estimate_gas(&tx(reverting, 0.into()), &state, &header) {
+		Err(CallError::Exceptional(_)) => {}
+		other => panic!("expected an exceptional call, got {:?}", other),
+	}
+}
+
+#[test]
+fn call_gas_is_capped() {
+	let spec = spec::new_test();
+	let mut config = ClientConfig::default();
+	config.rpc_gas_cap = 100_000.into();
+	let client = Client::new(
+		config,
+		&spec,
+		test_helpers::new_db(),
+		Arc::new(Miner::new_for_tests(&spec, None)),
+		IoChannel::disconnected(),
+	).unwrap();
+	let (mut state, header) = client.latest_state_and_header();
+
+	let looping = Address::from_low_u64_be(0x1009);
+	// JUMPDEST PUSH1 0 JUMP
+	state.init_code(&looping, "5b600056".from_hex().unwrap()).unwrap();
+
+	let tx = |to: Address| Transaction {
+		nonce: U256::zero(),
+		gas_price: U256::zero(),
+		gas: U256::from(1_000_000),
+		action: Action::Call(to),
+		value: U256::zero(),
+		data: vec![],
+	}.fake_sign(Address::from_low_u64_be(0x5e4d));
+
+	// a cheap call asking for more than the cap still runs.
+	let executed = client.call(&tx(Address::from_low_u64_be(0xf00)), Default::default(), &mut state.clone(), &header).unwrap();
+	assert_eq!(executed.exception, None);
+
+	assert_eq!(
+		client.call(&tx(looping), Default::default(), &mut state.clone(), &header).unwrap_err(),
+		CallError::GasCapExceeded(100_000.into()),
+	);
+	assert_eq!(
+		client.estimate_gas(&tx(looping), &state, &header).unwrap_err(),
+		CallError::GasCapExceeded(100_000.into()),
+	);
+}
+
+#[test]
+fn account_proof_is_rooted_in_state_root() {
+	use client_traits::ProvingBlockChainClient;
+
+	let client = generate_dummy_client(1);
+	let address = Address::from_str("102e61f5d8f9bc71d0ad4a084df4e65e05ce0e1c").unwrap();
+	let header = client.block_header(BlockId::Latest).unwrap();
+
+	let (proof, account) = client.prove_account(keccak(&address), BlockId::Latest).unwrap();
+	assert_eq!(account.balance, client.state().balance(&address).unwrap());
+	assert_eq!(account.nonce, client.state().nonce(&address).unwrap());
+	assert_eq!(account.storage_root, KECCAK_NULL_RLP);
+
+	// the proof walks from the state root down to the leaf holding the account.
+	assert!(!proof.is_empty());
+	assert_eq!(keccak(&proof[0]), header.state_root());
+	for pair in proof.windows(2) {
+		let child = keccak(&pair[1]);
+		assert!(pair[0].windows(32).any(|w| w == child.as_bytes()));
+	}
+	let leaf = ::rlp::Rlp::new(proof.last().unwrap());
+	assert_eq!(leaf.item_count().unwrap(), 2);
+	assert_eq!(leaf.at(1).unwrap().data().unwrap(), &::rlp::encode(&account)[..]);
+
+	// the account has no storage, so the proof is just the empty trie's root node.
+	let (proof, value) = client.prove_storage(keccak(&address), keccak(H256::zero()), BlockId::Latest).unwrap();
+	assert_eq!(value, H256::zero());
+	assert_eq!(proof, vec![NULL_RLP.to_vec()]);
+}
+
+#[test]
+fn state_availability_follows_pruning_window() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(10, EachBlockWith::Nothing);
+	client.set_history(Some(4));
+
+	assert_eq!(client.state_availability(BlockId::Latest), StateAvailability::Available);
+	assert_eq!(client.state_availability(BlockId::Number(7)), StateAvailability::Available);
+	assert_eq!(client.state_availability(BlockId::Number(6)), StateAvailability::Available);
+	assert_eq!(client.state_availability(BlockId::Number(5)), StateAvailability::Pruned);
+	assert_eq!(client.state_availability(BlockId::Hash(H256::repeat_byte(0xff))), StateAvailability::Unknown);
+
+	client.set_history(None);
+	assert_eq!(client.state_availability(BlockId::Number(0)), StateAvailability::Available);
+}
+
+#[test]
+fn uncles_by_block_and_index() {
+	let client = TestBlockChainClient::new();
+	client.add_blocks(2, EachBlockWith::Nothing);
+	let parent = client.chain_info().best_block_hash;
+
+	let uncle_headers: Vec<Header> = (0..2u64).map(|i| {
+		let mut uncle = Header::new();
+		uncle.set_number(2);
+		uncle.set_parent_hash(client.block_hash(BlockId::Number(1)).unwrap());
+		uncle.set_difficulty(U256::from(100 + i));
+		uncle
+	}).collect();
+	let mut uncles = RlpStream::new_list(2);
+	for uncle in &uncle_headers {
+		uncles.append(uncle);
+	}
+
+	let mut header = Header::new();
+	header.set_number(3);
+	header.set_parent_hash(parent);
+	header.set_uncles_hash(keccak(uncles.as_raw()));
+	let mut block = RlpStream::new_list(3);
+	block.append(&header);
+	block.append_raw(&::rlp::EMPTY_LIST_RLP, 1);
+	block.append_raw(uncles.as_raw(), 1);
+	client.import_block(Unverified::from_rlp(block.out()).unwrap()).unwrap();
+
+	let id = BlockId::Number(3);
+	assert_eq!(client.uncles_count(id), Some(2));
+	for (position, expected) in uncle_headers.iter().enumerate() {
+		let uncle = client.uncle(UncleId { block: id, position }).unwrap();
+		assert_eq!(uncle.hash(), expected.hash());
+	}
+	assert!(client.uncle(UncleId { block: id, position: 2 }).is_none());
+	assert_eq!(client.uncles_count(BlockId::Number(2)), Some(0));
+	assert_eq!(client.uncles_count(BlockId::Hash(H256::repeat_byte(0xff))), None);
+}
+
+#[test]
+fn block_summary_from_header_and_body() {
+	let client = TestBlockChainClient::new();
+	let author = Address::from_low_u64_be(0xa1);
+	client.add_block(EachBlockWith::Transactions(3), |mut header| {
+		header.set_author(author);
+		header.set_timestamp(1_000);
+		header.set_gas_used(63_000.into());
+		header
+	});
+
+	let summary = client.block_summary(BlockId::Latest).unwrap();
+	assert_eq!(summary, BlockSummary {
+		number: 1,
+		hash: client.chain_info().best_block_hash,
+		timestamp: 1_000,
+		gas_used: 63_000.into(),
+		gas_limit: 1_000_000.into(),
+		transaction_count: 3,
+		author,
+	});
+	assert_eq!(client.block_summary(BlockId::Number(0)).unwrap().transaction_count, 0);
+	assert!(client.block_summary(BlockId::Hash(H256::repeat_byte(0xff))).is_none());
+}
+
+#[test]
+fn transaction_status_follows_pool_and_chain() {
+	let key = KeyPair::from_secret(keccak("test").into()).unwrap();
+	let tx = |nonce: u64| Transaction {
+		nonce: nonce.into(),
+		gas_price: 0.into(),
+		gas: 21000.into(),
+		action: Action::Call(Address::zero()),
+		value: 0.into(),
+		data: Vec::new(),
+	}.sign(key.secret(), None);
+	let client = generate_dummy_client(0);
+	let test_spec = spec::new_test();
+
+	let ready = tx(0);
+	let future = tx(2);
+	assert_eq!(client.transaction_status(&ready.hash()), TransactionStatus::Unknown);
+
+	client.miner().import_own_transaction(&*client, PendingTransaction::new(ready.clone(), None)).unwrap();
+	client.miner().import_own_transaction(&*client, PendingTransaction::new(future.clone(), None)).unwrap();
+	assert_eq!(client.transaction_status(&ready.hash()), TransactionStatus::Pending);
+	assert_eq!(client.transaction_status(&future.hash()), TransactionStatus::Queued);
+
+	let mut b = client.prepare_open_block(Address::zero(), (3141562.into(), 31415620.into()), vec![]).unwrap();
+	b.push_transaction(ready.clone(), None).unwrap();
+	let b = b.close_and_lock().unwrap().seal(&*test_spec.engine, vec![]).unwrap();
+	client.import_sealed_block(b).unwrap();
+
+	let block = client.chain_info().best_block_hash;
+	assert_eq!(client.transaction_status(&ready.hash()), TransactionStatus::Mined { block, index: 0 });
+}
+
+#[test]
+fn account_exists_distinguishes_empty_from_absent() {
+	let key = KeyPair::from_secret(keccak("test").into()).unwrap();
+	let client = generate_dummy_client(0);
+	let test_spec = spec::new_test();
+	let funded = Address::from_str("102e61f5d8f9bc71d0ad4a084df4e65e05ce0e1c").unwrap();
+	let absent = Address::from_low_u64_be(0xdead);
+
+	// a zero-value, zero-price transaction leaves its sender present with a zero balance.
+	let tx = Transaction {
+		nonce: 0.into(),
+		gas_price: 0.into(),
+		gas: 21000.into(),
+		action: Action::Call(Address::zero()),
+		value: 0.into(),
+		data: Vec::new(),
+	}.sign(key.secret(), None);
+	assert_eq!(client.account_exists(&key.address(), BlockId::Latest), Some(false));
+
+	let mut b = client.prepare_open_block(Address::zero(), (3141562.into(), 31415620.into()), vec![]).unwrap();
+	b.push_transaction(tx, None).unwrap();
+	let b = b.close_and_lock().unwrap().seal(&*test_spec.engine, vec![]).unwrap();
+	client.import_sealed_block(b).unwrap();
+
+	assert_eq!(client.latest_balance(&key.address()), 0.into());
+	assert_eq!(client.account_exists(&key.address(), BlockId::Latest), Some(true));
+	assert_eq!(client.account_exists(&funded, BlockId::Latest), Some(true));
+	assert_eq!(client.latest_balance(&absent), 0.into());
+	assert_eq!(client.account_exists(&absent, BlockId::Latest), Some(false));
+}
+
+#[test]
+fn submit_seal_and_import_makes_sealed_block_best() {
+	use client_traits::EngineClient;
+
+	let client = generate_dummy_client(0);
+	let (pow_hash, number, _, _) = client.miner().work_package(&*client).unwrap();
+
+	let hash = client.submit_seal_and_import(pow_hash, vec![]).unwrap();
+
+	let info = client.chain_info();
+	assert_eq!(info.best_block_hash, hash);
+	assert_eq!(info.best_block_number, number);
+}
@@ -59,7 +59,7 @@ impl<'a> From<&'a VmError> for Error {
 			VmError::OutOfStack { .. } => Error::OutOfStack,
 			VmError::BuiltIn { .. } => Error::BuiltIn,
 			VmError::Wasm { .. } => Error::Wasm,
-			VmError::Internal(_) => Error::Internal,
+			VmError::Internal(_) | VmError::Timeout => Error::Internal,
 			VmError::MutableCallInStaticContext => Error::MutableCallInStaticContext,
 			VmError::OutOfBounds => Error::OutOfBounds,
 			VmError::Reverted => Error::Reverted,
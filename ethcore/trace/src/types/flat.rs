@@ -19,7 +19,8 @@
 use rlp::{Rlp, RlpStream, Decodable, Encodable, DecoderError};
 use rlp_derive::{RlpEncodableWrapper, RlpDecodableWrapper};
 use parity_util_mem::MallocSizeOf;
-use ethereum_types::Bloom;
+use ethereum_types::{Address, Bloom};
+use super::error::Error;
 use super::trace::{Action, Res};
 
 /// Trace localized in vector of traces produced by a single transaction.
@@ -72,6 +73,38 @@ impl Decodable for FlatTrace {
 	}
 }
 
+/// The most deeply nested failed call or create of a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DeepestFailure {
+	/// Callee of the failed call, `None` for a failed create.
+	pub address: Option<Address>,
+	/// Depth of the failed frame, 0 being the transaction itself.
+	pub depth: usize,
+	/// Why the frame failed.
+	pub error: Error,
+}
+
+/// Finds the most deeply nested failed frame among the traces of a single transaction.
+/// Of several failures at the same depth the earliest one is returned.
+///
+/// The failure is recovered from the call traces, so the transaction must have been
+/// executed with tracing enabled (e.g. `TransactOptions::with_tracing`); without traces
+/// this always returns `None`. `Executed::deepest_failure` reports the same frame
+/// without needing traces.
+pub fn deepest_failure(traces: &[FlatTrace]) -> Option<DeepestFailure> {
+	// `max_by_key` picks the last of equal elements, hence the reversal.
+	traces.iter().rev()
+		.filter_map(|trace| {
+			let (address, error) = match (&trace.action, &trace.result) {
+				(Action::Call(call), Res::FailedCall(error)) => (Some(call.to), error.clone()),
+				(Action::Create(_), Res::FailedCreate(error)) => (None, error.clone()),
+				_ => return None,
+			};
+			Some(DeepestFailure { address, depth: trace.trace_address.len(), error })
+		})
+		.max_by_key(|failure| failure.depth)
+}
+
 /// Represents all traces produced by a single transaction.
 #[derive(Debug, PartialEq, Clone, RlpEncodableWrapper, RlpDecodableWrapper, MallocSizeOf)]
 pub struct FlatTransactionTraces(pub(crate) Vec<FlatTrace>);
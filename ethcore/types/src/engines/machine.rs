@@ -92,4 +92,18 @@ pub struct Executed<T, V> {
 	pub vm_trace: Option<V>,
 	/// The state diff, if we traced it.
 	pub state_diff: Option<StateDiff>,
+	/// The most deeply nested call or create that failed, if any. This is recorded
+	/// without tracing and may be set even if the transaction itself succeeded.
+	pub deepest_failure: Option<FailedFrame>,
+}
+
+/// A call or create frame which failed during the execution of a transaction.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FailedFrame {
+	/// Callee of the failed call or address of the contract being created.
+	pub address: Address,
+	/// Depth of the failed frame, 0 being the transaction itself.
+	pub depth: usize,
+	/// Why the frame failed.
+	pub error: vm::Error,
 }
@@ -133,12 +133,20 @@ pub struct CommonParams {
 	pub transaction_permission_contract_transition: BlockNumber,
 	/// Maximum size of transaction's RLP payload
 	pub max_transaction_size: usize,
+	/// Base transaction gas cost, overriding the schedule preset.
+	pub tx_gas: Option<usize>,
+	/// Contract-creating transaction gas cost, overriding the schedule preset.
+	pub tx_create_gas: Option<usize>,
+	/// Gas cost of a zero byte of transaction data, overriding the schedule preset.
+	pub tx_data_zero_gas: Option<usize>,
+	/// Gas cost of a non-zero byte of transaction data, overriding the schedule preset.
+	pub tx_data_non_zero_gas: Option<usize>,
 }
 
 impl CommonParams {
 	/// Schedule for an EVM in the post-EIP-150-era of the Ethereum main net.
 	pub fn schedule(&self, block_number: u64) -> vm::Schedule {
-		if block_number < self.eip150_transition {
+		let mut schedule = if block_number < self.eip150_transition {
 			vm::Schedule::new_homestead()
 		} else {
 			let max_code_size = self.max_code_size(block_number);
@@ -151,7 +159,15 @@ impl CommonParams {
 
 			self.update_schedule(block_number, &mut schedule);
 			schedule
-		}
+		};
+
+		self.apply_gas_overrides(&mut schedule);
+		schedule
+	}
+
+	/// Overlay any transaction gas costs explicitly set in the spec onto the schedule.
+	pub fn apply_gas_overrides(&self, schedule: &mut vm::Schedule) {
+		schedule.override_tx_gas(self.tx_gas, self.tx_create_gas, self.tx_data_zero_gas, self.tx_data_non_zero_gas);
 	}
 
 	/// Returns max code size at given block.
@@ -359,6 +375,41 @@ impl From<ethjson::spec::Params> for CommonParams {
 				BlockNumber::max_value,
 				Into::into
 			),
+			tx_gas: p.tx_gas.map(Into::into),
+			tx_create_gas: p.tx_create_gas.map(Into::into),
+			tx_data_zero_gas: p.tx_data_zero_gas.map(Into::into),
+			tx_data_non_zero_gas: p.tx_data_non_zero_gas.map(Into::into),
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::CommonParams;
+
+	#[test]
+	fn gas_overrides_are_applied_to_schedule() {
+		let params = CommonParams {
+			tx_gas: Some(30000),
+			tx_data_non_zero_gas: Some(4),
+			..Default::default()
+		};
+
+		let preset = vm::Schedule::new_post_eip150(usize::max_value(), false, false, false);
+		for &block in &[0, 1_000_000] {
+			let schedule = params.schedule(block);
+			assert_eq!(schedule.tx_gas, 30000);
+			assert_eq!(schedule.tx_data_non_zero_gas, 4);
+			assert_eq!(schedule.tx_create_gas, preset.tx_create_gas);
+			assert_eq!(schedule.tx_data_zero_gas, preset.tx_data_zero_gas);
+		}
+
+		let homestead = CommonParams {
+			eip150_transition: u64::max_value(),
+			tx_gas: Some(30000),
+			..Default::default()
+		}.schedule(0);
+		assert_eq!(homestead.tx_gas, 30000);
+		assert_eq!(homestead.tx_create_gas, vm::Schedule::new_homestead().tx_create_gas);
+	}
+}
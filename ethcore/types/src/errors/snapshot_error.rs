@@ -70,6 +70,8 @@ pub enum SnapshotError {
 	WrongChunkFormat(String),
 	/// Unlinked ancient block chain; includes the parent hash where linkage failed
 	UnlinkedAncientBlockChain(H256),
+	/// Chunk contents don't match its hash (expected, found).
+	ChunkHashMismatch(H256, H256),
 }
 
 impl error::Error for SnapshotError {
@@ -111,6 +113,7 @@ impl fmt::Display for SnapshotError {
 			BadEpochProof(i) => write!(f, "Bad epoch proof for transition to epoch {}", i),
 			WrongChunkFormat(ref msg) => write!(f, "Wrong chunk format: {}", msg),
 			UnlinkedAncientBlockChain(parent_hash) => write!(f, "Unlinked ancient blocks chain at parent_hash={:#x}", parent_hash),
+			ChunkHashMismatch(ref expected, ref found) => write!(f, "Chunk hash mismatch. Expected {:?}, got {:?}", expected, found),
 		}
 	}
 }
@@ -80,6 +80,7 @@ pub mod receipt;
 pub mod security_level;
 pub mod snapshot;
 pub mod state_diff;
+pub mod state_override;
 pub mod trace_filter;
 pub mod transaction;
 pub mod tree_route;
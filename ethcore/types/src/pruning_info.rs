@@ -28,3 +28,14 @@ pub struct PruningInfo {
 	/// The first block where state requests may be served.
 	pub earliest_state: u64,
 }
+
+/// Availability of the state at a given block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateAvailability {
+	/// The state is stored and may be queried.
+	Available,
+	/// The block is known but its state has been pruned.
+	Pruned,
+	/// The block is not known to the client.
+	Unknown,
+}
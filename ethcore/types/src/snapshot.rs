@@ -39,7 +39,7 @@ pub enum Snapshotting {
 }
 
 /// A progress indicator for snapshots.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Progress {
 	/// Number of accounts processed so far
 	accounts: u64,
@@ -0,0 +1,37 @@
+// Copyright 2015-2020 Parity Technologies (UK) Ltd.
+// This file is part of Parity Ethereum.
+
+// Parity Ethereum is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Ethereum is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
+
+//! State overrides for simulated calls.
+
+use std::collections::BTreeMap;
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+
+/// Replacement values for a single account. Fields set to `None` keep their current value.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AccountOverride {
+	/// Balance to use instead of the current one.
+	pub balance: Option<U256>,
+	/// Nonce to use instead of the current one.
+	pub nonce: Option<U256>,
+	/// Code to use instead of the current one.
+	pub code: Option<Bytes>,
+	/// Storage slots to set. Slots which are not listed keep their current value.
+	pub storage: BTreeMap<H256, H256>,
+}
+
+/// Account modifications applied to a copy of the state before a simulated call.
+pub type StateOverride = BTreeMap<Address, AccountOverride>;
@@ -154,6 +154,8 @@ pub enum CallError {
 	StateCorrupt,
 	/// Error executing.
 	Execution(ExecutionError),
+	/// The call ran out of gas at the configured gas cap.
+	GasCapExceeded(U256),
 }
 
 impl From<ExecutionError> for CallError {
@@ -171,6 +173,7 @@ impl fmt::Display for CallError {
 			Exceptional(ref e) => format!("An exception ({}) happened in the execution", e),
 			StateCorrupt => "Stored state found to be corrupted.".into(),
 			Execution(ref e) => format!("{}", e),
+			GasCapExceeded(ref cap) => format!("Requires more gas than the cap of {}", cap),
 		};
 
 		f.write_fmt(format_args!("Transaction execution error ({}).", msg))
@@ -512,6 +512,24 @@ impl Deref for LocalizedTransaction {
 	}
 }
 
+/// Lifecycle state of a transaction as seen by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransactionStatus {
+	/// Neither in the transaction pool nor on the canon chain.
+	Unknown,
+	/// In the pool, but not ready to be included in a block yet (e.g. a nonce gap).
+	Queued,
+	/// In the pool and ready to be included in the next block.
+	Pending,
+	/// Included in the canon chain.
+	Mined {
+		/// Hash of the containing block.
+		block: H256,
+		/// Index within the block.
+		index: usize,
+	},
+}
+
 /// Queued transaction with additional information.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PendingTransaction {
@@ -19,6 +19,11 @@
 use ethereum_types::H256;
 
 /// Represents a tree route between `from` block and `to` block:
+///
+/// `blocks` always lists the blocks to retract first, walking back from `from`
+/// (exclusive of the ancestor), followed by the blocks to enact, walking forward
+/// to `to`. `index` is the boundary between the two parts, so
+/// `blocks[..index]` are retracted and `blocks[index..]` are enacted.
 #[derive(Debug)]
 pub struct TreeRoute {
 	/// A vector of hashes of all blocks, ordered from `from` to `to`.
@@ -30,3 +35,26 @@ pub struct TreeRoute {
 	/// Whether it has finalized blocks from `from` (inclusive) to `ancestor` (exclusive).
 	pub is_from_route_finalized: bool,
 }
+
+impl TreeRoute {
+	/// Best common ancestor of `from` and `to`.
+	pub fn common(&self) -> &H256 {
+		&self.ancestor
+	}
+
+	/// Blocks on the `from` branch which have to be retracted, starting with `from`.
+	pub fn retracted(&self) -> &[H256] {
+		&self.blocks[..self.index]
+	}
+
+	/// Blocks on the `to` branch which have to be enacted, ending with `to`.
+	pub fn enacted(&self) -> &[H256] {
+		&self.blocks[self.index..]
+	}
+
+	/// Whether moving from `from` to `to` retracts any blocks, i.e. `from` is not
+	/// an ancestor of `to`.
+	pub fn is_reorg(&self) -> bool {
+		self.index > 0
+	}
+}
@@ -83,6 +83,9 @@ pub enum Error {
 	OutOfBounds,
 	/// Execution has been reverted with REVERT.
 	Reverted,
+	/// Execution took longer than allowed. Only used for calls made outside of
+	/// block processing, such as `eth_call`.
+	Timeout,
 }
 
 impl From<Box<ethtrie::TrieError>> for Error {
@@ -112,6 +115,7 @@ impl fmt::Display for Error {
 			Wasm(ref msg) => write!(f, "Internal error: {}", msg),
 			OutOfBounds => write!(f, "Out of bounds"),
 			Reverted => write!(f, "Reverted"),
+			Timeout => write!(f, "Execution timed out"),
 		}
 	}
 }
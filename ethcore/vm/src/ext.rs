@@ -175,4 +175,7 @@ pub trait Ext {
 
 	/// Check if running in static context.
 	fn is_static(&self) -> bool;
+
+	/// Check if the execution has run past its deadline and should be aborted.
+	fn deadline_exceeded(&self) -> bool { false }
 }
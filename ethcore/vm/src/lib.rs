@@ -36,7 +36,7 @@ pub mod tests;
 pub use action_params::{ActionParams, ActionValue, ParamsType};
 pub use action_type::ActionType;
 pub use env_info::{EnvInfo, LastHashes};
-pub use schedule::{Schedule, VersionedSchedule, CleanDustMode, WasmCosts};
+pub use schedule::{Schedule, VersionedSchedule, CleanDustMode, ForkConfig, WasmCosts};
 pub use ext::{Ext, MessageCallResult, ContractCreateResult, CreateContractAddress};
 pub use return_data::{ReturnData, GasLeft};
 pub use error::{Error, Result, TrapResult, TrapError, TrapKind, ExecTrapResult, ExecTrapError};
@@ -211,7 +211,67 @@ pub enum CleanDustMode {
 	WithCodeAndStorage,
 }
 
+/// Activation block numbers of the Ethereum main net hard forks.
+/// `None` means the fork never activates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForkConfig {
+	/// Homestead transition block.
+	pub homestead: Option<u64>,
+	/// EIP-150 (Tangerine Whistle) transition block.
+	pub eip150: Option<u64>,
+	/// EIP-155/158/160/170 (Spurious Dragon) transition block.
+	pub eip158: Option<u64>,
+	/// Byzantium transition block.
+	pub byzantium: Option<u64>,
+	/// Constantinople transition block.
+	pub constantinople: Option<u64>,
+	/// Istanbul transition block.
+	pub istanbul: Option<u64>,
+}
+
 impl Schedule {
+	/// Replace the transaction gas costs that are given, keeping the others.
+	pub fn override_tx_gas(
+		&mut self,
+		tx_gas: Option<usize>,
+		tx_create_gas: Option<usize>,
+		tx_data_zero_gas: Option<usize>,
+		tx_data_non_zero_gas: Option<usize>,
+	) {
+		if let Some(tx_gas) = tx_gas {
+			self.tx_gas = tx_gas;
+		}
+		if let Some(tx_create_gas) = tx_create_gas {
+			self.tx_create_gas = tx_create_gas;
+		}
+		if let Some(tx_data_zero_gas) = tx_data_zero_gas {
+			self.tx_data_zero_gas = tx_data_zero_gas;
+		}
+		if let Some(tx_data_non_zero_gas) = tx_data_non_zero_gas {
+			self.tx_data_non_zero_gas = tx_data_non_zero_gas;
+		}
+	}
+
+	/// Schedule of the latest fork in `forks` that is active at block `number`.
+	pub fn for_block(number: u64, forks: &ForkConfig) -> Schedule {
+		let active = |fork: Option<u64>| fork.map_or(false, |n| number >= n);
+		if active(forks.istanbul) {
+			Self::new_istanbul()
+		} else if active(forks.constantinople) {
+			Self::new_constantinople()
+		} else if active(forks.byzantium) {
+			Self::new_byzantium()
+		} else if active(forks.eip158) {
+			Self::new_post_eip150(24576, true, true, true)
+		} else if active(forks.eip150) {
+			Self::new_post_eip150(usize::max_value(), false, false, false)
+		} else if active(forks.homestead) {
+			Self::new_homestead()
+		} else {
+			Self::new_frontier()
+		}
+	}
+
 	/// Schedule for the Frontier-era of the Ethereum main net.
 	pub fn new_frontier() -> Schedule {
 		Self::new(false, false, 21000)
@@ -403,3 +463,22 @@ fn schedule_evm_assumptions() {
 	assert_eq!(s1.quad_coeff_div, 512);
 	assert_eq!(s2.quad_coeff_div, 512);
 }
+
+#[test]
+#[cfg(test)]
+fn schedule_for_block_follows_forks() {
+	let forks = ForkConfig {
+		homestead: Some(10),
+		byzantium: Some(20),
+		..Default::default()
+	};
+
+	assert_eq!(Schedule::for_block(0, &forks).tx_create_gas, 21000);
+	assert_eq!(Schedule::for_block(9, &forks).tx_create_gas, 21000);
+	assert_eq!(Schedule::for_block(10, &forks).tx_create_gas, 53000);
+	assert!(!Schedule::for_block(19, &forks).have_revert);
+	assert!(Schedule::for_block(20, &forks).have_revert);
+	assert!(!Schedule::for_block(u64::max_value(), &forks).have_bitwise_shifting);
+	assert_eq!(Schedule::for_block(1_000, &ForkConfig::default()).tx_create_gas, 21000);
+}
+
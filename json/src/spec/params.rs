@@ -141,6 +141,33 @@ pub struct Params {
 	pub kip4_transition: Option<Uint>,
 	/// KIP6 activiation block height.
 	pub kip6_transition: Option<Uint>,
+	/// Override for the base gas cost of a transaction.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub tx_gas: Option<Uint>,
+	/// Override for the base gas cost of a contract-creating transaction.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub tx_create_gas: Option<Uint>,
+	/// Override for the gas cost of a zero byte of transaction data.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub tx_data_zero_gas: Option<Uint>,
+	/// Override for the gas cost of a non-zero byte of transaction data.
+	#[serde(default, deserialize_with="uint::validate_optional_non_zero")]
+	pub tx_data_non_zero_gas: Option<Uint>,
+}
+
+impl Params {
+	/// Check the transaction gas overrides. A spec overriding any transaction gas cost
+	/// must also set `txGas`, the base cost the others are defined against.
+	pub fn validate_tx_gas(&self) -> Result<(), String> {
+		let overrides_tx_gas = self.tx_create_gas.is_some()
+			|| self.tx_data_zero_gas.is_some()
+			|| self.tx_data_non_zero_gas.is_some();
+
+		if overrides_tx_gas && self.tx_gas.is_none() {
+			return Err("txGas must be set when overriding transaction gas costs".into());
+		}
+		Ok(())
+	}
 }
 
 #[cfg(test)]
@@ -174,6 +201,24 @@ mod tests {
 		assert_eq!(deserialized.wasm_activation_transition, Some(Uint(U256::from(0x1010))));
 	}
 
+	#[test]
+	fn params_gas_overrides_deserialization() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID": "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"txGas": "0x5208",
+			"txDataNonZeroGas": "0x10"
+		}"#;
+
+		let deserialized: Params = serde_json::from_str(s).unwrap();
+		assert_eq!(deserialized.tx_gas, Some(Uint(U256::from(21000))));
+		assert_eq!(deserialized.tx_create_gas, None);
+		assert_eq!(deserialized.tx_data_zero_gas, None);
+		assert_eq!(deserialized.tx_data_non_zero_gas, Some(Uint(U256::from(16))));
+	}
+
 	#[test]
 	#[should_panic(expected = "a non-zero value")]
 	fn test_zero_value_divisor() {
@@ -190,4 +235,53 @@ mod tests {
 
 		let _deserialized: Params = serde_json::from_str(s).unwrap();
 	}
+
+	#[test]
+	#[should_panic(expected = "a non-zero value")]
+	fn test_zero_tx_gas_override() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID": "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"txGas": "0x0"
+		}"#;
+
+		let _deserialized: Params = serde_json::from_str(s).unwrap();
+	}
+
+	#[test]
+	#[should_panic(expected = "a non-zero value")]
+	fn test_zero_tx_data_gas_override() {
+		let s = r#"{
+			"maximumExtraDataSize": "0x20",
+			"networkID": "0x1",
+			"minGasLimit": "0x1388",
+			"gasLimitBoundDivisor": "0x20",
+			"txGas": "0x5208",
+			"txDataZeroGas": "0x0"
+		}"#;
+
+		let _deserialized: Params = serde_json::from_str(s).unwrap();
+	}
+
+	#[test]
+	fn tx_gas_overrides_require_tx_gas() {
+		let params = |overrides: &str| -> Params {
+			serde_json::from_str(&format!(r#"{{
+				"maximumExtraDataSize": "0x20",
+				"networkID": "0x1",
+				"minGasLimit": "0x1388",
+				"gasLimitBoundDivisor": "0x20"{}
+			}}"#, overrides)).unwrap()
+		};
+
+		assert!(params("").validate_tx_gas().is_ok());
+		assert!(params(r#", "txGas": "0x7530""#).validate_tx_gas().is_ok());
+		assert!(params(r#", "txGas": "0x7530", "txDataNonZeroGas": "0x4""#).validate_tx_gas().is_ok());
+		assert_eq!(
+			params(r#", "txDataNonZeroGas": "0x4""#).validate_tx_gas(),
+			Err("txGas must be set when overriding transaction gas costs".into()),
+		);
+	}
 }
@@ -76,7 +76,9 @@ pub struct Spec {
 impl Spec {
 	/// Loads test from json.
 	pub fn load<R>(reader: R) -> Result<Self, Error> where R: Read {
-		serde_json::from_reader(reader)
+		let spec: Spec = serde_json::from_reader(reader)?;
+		spec.params.validate_tx_gas().map_err(serde::de::Error::custom)?;
+		Ok(spec)
 	}
 }
 
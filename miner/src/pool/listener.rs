@@ -74,6 +74,25 @@ impl txpool::Listener<Transaction> for Logger {
 	}
 }
 
+/// Collects the transactions dropped from the pool because of its limits.
+#[derive(Default, Debug)]
+pub struct Evictions {
+	evicted: Vec<H256>,
+}
+
+impl Evictions {
+	/// Take the hashes of the transactions dropped since the last call.
+	pub fn take(&mut self) -> Vec<H256> {
+		std::mem::replace(&mut self.evicted, Vec::new())
+	}
+}
+
+impl txpool::Listener<Transaction> for Evictions {
+	fn dropped(&mut self, tx: &Arc<Transaction>, _new: Option<&Transaction>) {
+		self.evicted.push(*tx.hash());
+	}
+}
+
 /// Transactions pool notifier
 #[derive(Default)]
 pub struct TransactionsPoolNotifier {
@@ -33,7 +33,7 @@ use pool::{
 };
 use pool::local_transactions::LocalTransactionsList;
 
-type Listener = (LocalTransactionsList, (listener::TransactionsPoolNotifier, listener::Logger));
+type Listener = (LocalTransactionsList, (listener::TransactionsPoolNotifier, (listener::Logger, listener::Evictions)));
 type Pool = txpool::Pool<pool::VerifiedTransaction, scoring::NonceAndGasPrice, Listener>;
 
 /// Max cache time in milliseconds for pending transactions.
@@ -52,6 +52,17 @@ const TIMESTAMP_CACHE: u64 = 1000;
 /// This parameter controls how many (best) senders at once will be processed.
 const CULL_SENDERS_CHUNK: usize = 1024;
 
+/// Outcome of importing a batch of transactions.
+#[derive(Debug, PartialEq)]
+pub struct ImportOutcome {
+	/// Result of every transaction, in import order.
+	pub results: Vec<Result<(), transaction::Error>>,
+	/// Hashes of pooled transactions pushed out to stay within the pool limits.
+	///
+	/// Local transactions are never pushed out by non-local ones.
+	pub evicted: Vec<H256>,
+}
+
 /// Transaction queue status.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Status {
@@ -246,6 +257,19 @@ impl TransactionQueue {
 		client: C,
 		transactions: T,
 	) -> Vec<Result<(), transaction::Error>> {
+		self.import_with_outcome(client, transactions).results
+	}
+
+	/// Import a set of transactions to the pool, reporting which pooled transactions
+	/// had to be evicted to make room for them.
+	///
+	/// When the pool is full the transactions with the lowest gas price go first
+	/// (the most recently inserted of equally priced ones).
+	pub fn import_with_outcome<T: IntoIterator<Item = verifier::Transaction>, C: client::Client + client::NonceClient + Clone>(
+		&self,
+		client: C,
+		transactions: T,
+	) -> ImportOutcome {
 		// Run verification
 		trace_time!("pool::verify_and_import");
 		let options = self.options.read().clone();
@@ -303,13 +327,18 @@ impl TransactionQueue {
 			.collect::<Vec<_>>();
 
 		// Notify about imported transactions.
-		(self.pool.write().listener_mut().1).0.notify();
+		let evicted = {
+			let mut pool = self.pool.write();
+			let listener = &mut pool.listener_mut().1;
+			listener.0.notify();
+			(listener.1).1.take()
+		};
 
 		if results.iter().any(|r| r.is_ok()) {
 			self.cached_pending.write().clear();
 		}
 
-		results
+		ImportOutcome { results, evicted }
 	}
 
 	/// Returns all transactions in the queue without explicit ordering.
@@ -426,7 +455,7 @@ impl TransactionQueue {
 	}
 
 	/// Culls all stalled transactions from the pool.
-	pub fn cull<C: client::NonceClient + Clone>(
+	pub fn cull<C: client::NonceClient + client::Client + Clone>(
 		&self,
 		client: C,
 	) {
@@ -456,7 +485,9 @@ impl TransactionQueue {
 		for chunk in senders.chunks(CULL_SENDERS_CHUNK) {
 			trace_time!("pool::cull::chunk");
 			let state_readiness = ready::State::new(client.clone(), stale_id, nonce_cap);
-			removed += self.pool.write().cull(Some(chunk), state_readiness);
+			// Transactions which the sender can no longer afford (e.g. after a reorg) are dropped as well.
+			let balance_readiness = ready::Balance::new(client.clone());
+			removed += self.pool.write().cull(Some(chunk), (state_readiness, balance_readiness));
 		}
 		debug!(target: "txqueue", "Removed {} stalled transactions. {}", removed, self.status());
 	}
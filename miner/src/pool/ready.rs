@@ -45,7 +45,7 @@ use ethereum_types::{U256, H160 as Address};
 use txpool::{self, VerifiedTransaction as PoolVerifiedTransaction};
 use types::transaction;
 
-use super::client::NonceClient;
+use super::client::{Client, NonceClient};
 use super::VerifiedTransaction;
 
 /// Checks readiness of transactions by comparing the nonce to state nonce.
@@ -102,6 +102,44 @@ impl<C: NonceClient> txpool::Ready<VerifiedTransaction> for State<C> {
 	}
 }
 
+/// Checks if the sender can still pay for the transaction.
+///
+/// Transactions that cost more than the current balance of the sender are marked as stale.
+/// Meant to be combined with `State`, so that only transactions that are next in line are checked.
+/// Transactions of a sender are expected in nonce order, every ready one is paid for from the balance
+/// left over by its predecessors.
+#[derive(Debug)]
+pub struct Balance<C> {
+	balances: HashMap<Address, U256>,
+	state: C,
+}
+
+impl<C> Balance<C> {
+	/// Create new Balance checker, given client interface.
+	pub fn new(state: C) -> Self {
+		Balance {
+			balances: Default::default(),
+			state,
+		}
+	}
+}
+
+impl<C: Client> txpool::Ready<VerifiedTransaction> for Balance<C> {
+	fn is_ready(&mut self, tx: &VerifiedTransaction) -> txpool::Readiness {
+		let sender = tx.sender();
+		let state = &self.state;
+		let balance = self.balances.entry(*sender).or_insert_with(|| state.account_details(sender).balance);
+		let tx = &tx.transaction;
+		let cost = tx.value.saturating_add(tx.gas.saturating_mul(tx.gas_price));
+		if cost > *balance {
+			txpool::Readiness::Stale
+		} else {
+			*balance -= cost;
+			txpool::Readiness::Ready
+		}
+	}
+}
+
 /// Checks readines of Pending transactions by comparing it with current time and block number.
 #[derive(Debug)]
 pub struct Condition {
@@ -229,6 +267,22 @@ mod tests {
 		assert_eq!(res, txpool::Readiness::Stale);
 	}
 
+	#[test]
+	fn should_deduct_cost_of_ready_transactions_from_balance() {
+		// given
+		// each transaction costs 100 wei of value plus 21_000 wei of gas
+		let (tx1, tx2) = Tx::default().signed_pair().verified();
+		let mut ready = Balance::new(TestClient::new().with_balance(30_000));
+
+		// when
+		let res1 = ready.is_ready(&tx1);
+		let res2 = ready.is_ready(&tx2);
+
+		// then
+		assert_eq!(res1, txpool::Readiness::Ready);
+		assert_eq!(res2, txpool::Readiness::Stale);
+	}
+
 	#[test]
 	fn should_check_readiness_of_condition() {
 		// given
@@ -392,6 +392,26 @@ fn should_correctly_update_futures_when_removing() {
 	assert_eq!(txq.status().status.transaction_count, 0);
 }
 
+#[test]
+fn should_cull_transactions_sender_can_no_longer_afford() {
+	// given
+	let txq = new_queue();
+	let tx = Tx::default().signed();
+
+	let res = txq.import(TestClient::new(), vec![tx].retracted());
+	assert_eq!(res, vec![Ok(())]);
+	assert_eq!(txq.status().status.transaction_count, 1);
+
+	// when
+	// the transaction costs 100 wei of value plus 21_000 wei of gas
+	txq.cull(TestClient::new().with_balance(21_100));
+	assert_eq!(txq.status().status.transaction_count, 1);
+	txq.cull(TestClient::new().with_balance(21_099));
+
+	// then
+	assert_eq!(txq.status().status.transaction_count, 0);
+}
+
 #[test]
 fn should_move_transactions_if_gap_filled() {
 	// given
@@ -514,6 +534,51 @@ fn should_prefer_current_transactions_when_hitting_the_limit() {
 	assert_eq!(txq.next_nonce(TestClient::new(), &sender), Some(124.into()));
 }
 
+#[test]
+fn should_evict_cheapest_transactions_when_queue_is_full() {
+	// given
+	let txq = new_queue();
+	let cheap = vec![Tx::gas_price(2).signed(), Tx::gas_price(2).signed(), Tx::gas_price(2).signed()];
+	let res = txq.import(TestClient::new(), cheap.clone().retracted());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// when
+	let pricey = Tx::gas_price(3).signed();
+	let hash = pricey.hash();
+	let res = txq.import_with_outcome(TestClient::new(), vec![pricey].retracted());
+	let res2 = txq.import_with_outcome(TestClient::new(), vec![Tx::gas_price(1).signed()].retracted());
+
+	// then
+	assert_eq!(res.results, vec![Ok(())]);
+	assert_eq!(res.evicted.len(), 1);
+	assert!(cheap.iter().any(|tx| tx.hash() == res.evicted[0]));
+	assert_eq!(res2.results, vec![Err(transaction::Error::TooCheapToReplace { prev: Some(2.into()), new: Some(1.into()) })]);
+	assert!(res2.evicted.is_empty());
+	assert_eq!(txq.status().status.transaction_count, 3);
+	let top = txq.pending(TestClient::new(), PendingSettings::all_prioritized(0, 0));
+	assert_eq!(top[0].hash, hash);
+	assert!(top.iter().all(|tx| tx.hash != res.evicted[0]));
+}
+
+#[test]
+fn should_never_evict_local_transactions() {
+	// given
+	let txq = new_queue();
+	let local = vec![Tx::gas_price(2).signed(), Tx::gas_price(2).signed(), Tx::gas_price(2).signed()];
+	let res = txq.import(TestClient::new(), local.clone().local());
+	assert_eq!(res, vec![Ok(()), Ok(()), Ok(())]);
+
+	// when
+	let res = txq.import_with_outcome(TestClient::new(), vec![Tx::gas_price(10).signed()].unverified());
+
+	// then
+	assert!(res.results[0].is_err());
+	assert!(res.evicted.is_empty());
+	for tx in &local {
+		assert!(txq.find(&tx.hash()).is_some());
+	}
+}
+
 #[test]
 fn should_drop_transactions_with_old_nonces() {
 	let txq = new_queue();
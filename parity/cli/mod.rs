@@ -530,6 +530,18 @@ usage! {
 			"--poll-lifetime=[S]",
 			"Set the RPC filter lifetime to S seconds. The filter has to be polled at least every S seconds , otherwise it is removed.",
 
+			ARG arg_jsonrpc_call_timeout: (Option<u64>) = None, or |c: &Config| c.rpc.as_ref()?.call_timeout,
+			"--jsonrpc-call-timeout=[MS]",
+			"Abort eth_call and similar read-only executions that run for longer than MS milliseconds.",
+
+			ARG arg_jsonrpc_max_logs: (Option<usize>) = None, or |c: &Config| c.rpc.as_ref()?.max_logs,
+			"--jsonrpc-max-logs=[N]",
+			"Reject eth_getLogs requests without an explicit limit that match more than N logs.",
+
+			ARG arg_jsonrpc_max_block_range: (Option<u64>) = None, or |c: &Config| c.rpc.as_ref()?.max_block_range,
+			"--jsonrpc-max-block-range=[N]",
+			"Reject eth_getLogs requests spanning more than N blocks.",
+
 		["API and Console Options – WebSockets"]
 			FLAG flag_no_ws: (bool) = false, or |c: &Config| c.websockets.as_ref()?.disable.clone(),
 			"--no-ws",
@@ -1275,6 +1287,9 @@ struct Rpc {
 	keep_alive: Option<bool>,
 	experimental_rpcs: Option<bool>,
 	poll_lifetime: Option<u32>,
+	call_timeout: Option<u64>,
+	max_logs: Option<usize>,
+	max_block_range: Option<u64>,
 	allow_missing_blocks: Option<bool>,
 }
 
@@ -1830,6 +1845,9 @@ mod tests {
 			arg_jsonrpc_threads: None, // DEPRECATED, does nothing
 			arg_jsonrpc_max_payload: None,
 			arg_poll_lifetime: 60u32,
+			arg_jsonrpc_call_timeout: None,
+			arg_jsonrpc_max_logs: None,
+			arg_jsonrpc_max_block_range: None,
 			flag_jsonrpc_allow_missing_blocks: false,
 
 			// WS
@@ -2111,6 +2129,9 @@ mod tests {
 				keep_alive: None,
 				experimental_rpcs: None,
 				poll_lifetime: None,
+				call_timeout: None,
+				max_logs: None,
+				max_block_range: None,
 				allow_missing_blocks: None
 			}),
 			ipc: Some(Ipc {
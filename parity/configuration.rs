@@ -375,6 +375,9 @@ impl Configuration {
 				miner_options: self.miner_options()?,
 				gas_price_percentile: self.args.arg_gas_price_percentile,
 				poll_lifetime: self.args.arg_poll_lifetime,
+				call_timeout: self.args.arg_jsonrpc_call_timeout.map(Duration::from_millis),
+				max_logs: self.args.arg_jsonrpc_max_logs,
+				max_block_range: self.args.arg_jsonrpc_max_block_range,
 				ws_conf,
 				snapshot_conf,
 				http_conf,
@@ -1430,6 +1433,9 @@ mod tests {
 			miner_options: Default::default(),
 			gas_price_percentile: 50,
 			poll_lifetime: 60,
+			call_timeout: None,
+			max_logs: None,
+			max_block_range: None,
 			ws_conf: Default::default(),
 			http_conf: Default::default(),
 			ipc_conf: Default::default(),
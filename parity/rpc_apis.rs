@@ -253,6 +253,8 @@ pub struct FullDependencies {
 	pub executor: Executor,
 	pub gas_price_percentile: usize,
 	pub poll_lifetime: u32,
+	pub max_logs: Option<usize>,
+	pub max_block_range: Option<u64>,
 	pub allow_missing_blocks: bool,
 	pub no_ancient_blocks: bool,
 }
@@ -306,7 +308,9 @@ impl FullDependencies {
 							gas_price_percentile: self.gas_price_percentile,
 							allow_missing_blocks: self.allow_missing_blocks,
 							allow_experimental_rpcs: self.experimental_rpcs,
-							no_ancient_blocks: self.no_ancient_blocks
+							no_ancient_blocks: self.no_ancient_blocks,
+							max_logs: self.max_logs,
+							max_block_range: self.max_block_range,
 						}
 					);
 					handler.extend_with(client.to_delegate());
@@ -98,6 +98,12 @@ pub struct RunCmd {
 	pub miner_options: MinerOptions,
 	pub gas_price_percentile: usize,
 	pub poll_lifetime: u32,
+	/// Maximal time a single `eth_call` may run for. No limit if `None`.
+	pub call_timeout: Option<Duration>,
+	/// Maximal number of logs returned by an `eth_getLogs` without a limit. No limit if `None`.
+	pub max_logs: Option<usize>,
+	/// Maximal number of blocks an `eth_getLogs` filter may span. No limit if `None`.
+	pub max_block_range: Option<u64>,
 	pub ws_conf: rpc::WsConfiguration,
 	pub http_conf: rpc::HttpConfiguration,
 	pub ipc_conf: rpc::IpcConfiguration,
@@ -543,6 +549,7 @@ fn execute_impl<Cr, Rr>(
 	client_config.queue.verifier_settings = cmd.verifier_settings;
 	client_config.transaction_verification_queue_size = ::std::cmp::max(2048, txpool_size / 4);
 	client_config.snapshot = cmd.snapshot_conf.clone();
+	client_config.call_timeout = cmd.call_timeout;
 
 	// set up bootnodes
 	let mut net_conf = cmd.net_conf;
@@ -731,6 +738,8 @@ fn execute_impl<Cr, Rr>(
 		private_tx_service: Some(private_tx_service.clone()),
 		gas_price_percentile: cmd.gas_price_percentile,
 		poll_lifetime: cmd.poll_lifetime,
+		max_logs: cmd.max_logs,
+		max_block_range: cmd.max_block_range,
 		allow_missing_blocks: cmd.allow_missing_blocks,
 		no_ancient_blocks: !cmd.download_old_blocks,
 	});
@@ -147,6 +147,7 @@ impl SecretStoreChain for TrustedClient {
 		let signature = self.self_key_pair.sign(&transaction.hash(chain_id))?;
 		let signed = SignedTransaction::new(transaction.with_signature(signature, chain_id))?;
 		miner.import_own_transaction(&*client, signed.into())
+			.map(|_| ())
 			.map_err(|e| EthKeyError::Custom(format!("failed to import tx: {}", e)))
 	}
 
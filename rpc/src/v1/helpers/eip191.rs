@@ -59,3 +59,44 @@ fn map_serde_err<T: Display>(struct_name: &'static str) -> impl Fn(T) -> Error {
 		errors::invalid_call_data(format!("Error deserializing '{}': {}", struct_name, error))
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::to_value;
+
+	#[test]
+	fn hash_personal_message() {
+		// keccak256("\x19Ethereum Signed Message:\n5hello")
+		let message = to_value(Bytes::new(b"hello".to_vec())).unwrap();
+		let hash = hash_message(EIP191Version::PersonalMessage, message).unwrap();
+		assert_eq!(
+			hash,
+			"50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750".parse().unwrap()
+		);
+	}
+
+	#[test]
+	fn hash_presigned_transaction() {
+		let validator = [0x11u8; 20];
+		let message = to_value(PresignedTransaction {
+			validator: validator.into(),
+			data: Bytes::new(vec![0xde, 0xad]),
+		}).unwrap();
+		let hash = hash_message(EIP191Version::PresignedTransaction, message).unwrap();
+		let expected = keccak([&b"\x19\x00"[..], &validator[..], &[0xde, 0xad][..]].concat());
+		assert_eq!(hash, expected);
+	}
+
+	#[test]
+	fn reject_unsupported_version() {
+		let err = from_value::<EIP191Version>(Value::String("0x02".into())).unwrap_err();
+		assert!(err.to_string().contains("Invalid byte version '0x02'"));
+	}
+
+	#[test]
+	fn reject_malformed_message() {
+		let err = hash_message(EIP191Version::PersonalMessage, Value::Bool(true)).unwrap_err();
+		assert!(err.message.contains("Error deserializing 'Bytes'"));
+	}
+}
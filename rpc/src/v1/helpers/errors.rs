@@ -48,6 +48,7 @@ mod codes {
 	pub const EXECUTION_ERROR: i64 = -32015;
 	pub const EXCEPTION_ERROR: i64 = -32016;
 	pub const DATABASE_ERROR: i64 = -32017;
+	pub const CALL_TIMEOUT: i64 = -32018;
 	#[cfg(any(test, feature = "accounts"))]
 	pub const ACCOUNT_LOCKED: i64 = -32020;
 	#[cfg(any(test, feature = "accounts"))]
@@ -123,6 +124,22 @@ pub fn request_rejected_param_limit(limit: u64, items_desc: &str) -> Error {
 	}
 }
 
+pub fn filter_block_range_too_wide(limit: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::REQUEST_REJECTED_LIMIT),
+		message: format!("Filter spans more than {} blocks. Narrow the range with fromBlock/toBlock or query a single blockHash.", limit),
+		data: None,
+	}
+}
+
+pub fn filter_block_before_horizon(horizon: u64) -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::UNSUPPORTED_REQUEST),
+		message: format!("Filter starts before the pruning horizon #{}. Older blocks are no longer available on this node.", horizon),
+		data: None,
+	}
+}
+
 pub fn account<T: fmt::Debug>(error: &str, details: T) -> Error {
 	Error {
 		code: ErrorCode::ServerError(codes::ACCOUNT_ERROR),
@@ -505,13 +522,41 @@ pub fn call(error: CallError) -> Error {
 		CallError::StateCorrupt => state_corrupt(),
 		CallError::Exceptional(e) => exceptional(e),
 		CallError::Execution(e) => execution(e),
+		CallError::GasCapExceeded(cap) => Error {
+			code: ErrorCode::ServerError(codes::EXECUTION_ERROR),
+			message: format!("Call exceeds the configured gas cap of {}.", cap),
+			data: None,
+		},
 		CallError::TransactionNotFound => internal("{}, this should not be the case with eth_call, most likely a bug.", CallError::TransactionNotFound),
 	}
 }
 
+pub fn estimate_gas(error: CallError) -> Error {
+	match error {
+		CallError::Exceptional(e) => Error {
+			code: ErrorCode::ServerError(codes::EXCEPTION_ERROR),
+			message: "Gas estimation failed: the transaction fails even with the highest gas allowance the node will try.".into(),
+			data: Some(Value::String(e.to_string())),
+		},
+		e => call(e),
+	}
+}
+
+pub fn call_timeout() -> Error {
+	Error {
+		code: ErrorCode::ServerError(codes::CALL_TIMEOUT),
+		message: "Call execution timed out.".into(),
+		data: None,
+	}
+}
+
 pub fn vm(error: &VMError, output: &[u8]) -> Error {
 	use rustc_hex::ToHex;
 
+	if let VMError::Timeout = *error {
+		return call_timeout();
+	}
+
 	let data = match error {
 		&VMError::Reverted => format!("{} 0x{}", VMError::Reverted, output.to_hex()),
 		error => format!("{}", error),
@@ -38,12 +38,17 @@ pub fn verify_signature(
 	};
 	let v = v.as_u64();
 	let is_valid_for_current_chain = match (chain_id, v) {
-		(None, v) if v == 0 || v == 1 => true,
+		(None, v) if v <= 1 || v == 27 || v == 28 => true,
 		(Some(chain_id), v) if v >= 35 => (v - 35) / 2 == chain_id,
 		_ => false,
 	};
 
-	let v = if v >= 35 { (v - 1) % 2 } else { v };
+	// EIP-155 (`chain_id * 2 + 35 + v`) and legacy (`27 + v`) encodings.
+	let v = match v {
+		v if v >= 35 => (v - 1) % 2,
+		27 | 28 => v - 27,
+		v => v,
+	};
 
 	let signature = Signature::from_rsv(&r, &s, v as u8);
 	let public_key = recover(&signature, &hash).map_err(errors::encryption)?;
@@ -54,7 +59,7 @@ pub fn verify_signature(
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crypto::publickey::{Generator, Random};
+	use crypto::publickey::{Generator, Random, Public};
 	use ethereum_types::{H160, U64};
 
 	pub fn add_chain_replay_protection(v: u64, chain_id: Option<u64>) -> u64 {
@@ -69,10 +74,11 @@ mod tests {
 	}
 
 	/// mocked signer
-	fn sign(should_prefix: bool, data: Vec<u8>, signing_chain_id: Option<u64>) -> (H160, [u8; 32], [u8; 32], U64) {
+	fn sign(should_prefix: bool, data: Vec<u8>, signing_chain_id: Option<u64>) -> (H160, Public, [u8; 32], [u8; 32], U64) {
 		let hash = if should_prefix { eth_data_hash(data) } else { keccak(data) };
 		let account = Random.generate().unwrap();
 		let address = account.address();
+		let public = *account.public();
 		let sig = crypto::publickey::sign(account.secret(), &hash).unwrap();
 		let (r, s, v) = (sig.r(), sig.s(), sig.v());
 		let v = add_chain_replay_protection(v as u64, signing_chain_id);
@@ -82,17 +88,18 @@ mod tests {
 			s_buf.copy_from_slice(s);
 			(r_buf, s_buf)
 		};
-		(address.into(), r_buf, s_buf, v.into())
+		(address.into(), public, r_buf, s_buf, v.into())
 	}
 
 	fn run_test(test_case: TestCase) {
 		let TestCase { should_prefix, signing_chain_id, rpc_chain_id, is_valid_for_current_chain } = test_case;
 		let data = vec![5u8];
 
-		let (address, r, s, v) = sign(should_prefix, data.clone(), signing_chain_id);
+		let (address, public, r, s, v) = sign(should_prefix, data.clone(), signing_chain_id);
 		let account = verify_signature(should_prefix, data.into(), r.into(), s.into(), v, rpc_chain_id).unwrap();
 
 		assert_eq!(account.address, address.into());
+		assert_eq!(account.public_key, public);
 		assert_eq!(account.is_valid_for_current_chain, is_valid_for_current_chain)
 	}
 
@@ -164,6 +171,22 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn test_verify_signature_legacy_v() {
+		let data = vec![5u8];
+		let (address, public, r, s, v) = sign(true, data.clone(), None);
+		let legacy_v = U64::from(v.as_u64() + 27);
+
+		let account = verify_signature(true, data.clone().into(), r.into(), s.into(), legacy_v, None).unwrap();
+		assert_eq!(account.address, address.into());
+		assert_eq!(account.public_key, public);
+		assert!(account.is_valid_for_current_chain);
+
+		let account = verify_signature(true, data.into(), r.into(), s.into(), legacy_v, Some(1)).unwrap();
+		assert_eq!(account.address, address.into());
+		assert!(!account.is_valid_for_current_chain);
+	}
+
 	#[test]
 	fn test_verify_signature_no_chain_replay_protection() {
 		run_test(TestCase {
@@ -40,6 +40,7 @@ use types::{
 	header::Header,
 	ids::{BlockId, TransactionId, UncleId},
 	filter::Filter as EthcoreFilter,
+	log_entry::LocalizedLogEntry,
 	transaction::{SignedTransaction, LocalizedTransaction},
 	snapshot::RestorationStatus,
 };
@@ -53,7 +54,7 @@ use v1::helpers::dispatch::{FullDispatcher, default_gas_price};
 use v1::traits::Eth;
 use v1::types::{
 	RichBlock, Block, BlockTransactions, BlockNumber, Bytes, SyncStatus, SyncInfo,
-	Transaction, CallRequest, Index, Filter, Log, Receipt, Work, EthAccount, StorageProof,
+	Transaction, CallRequest, Index, Filter, Log, LogCursor, Receipt, Work, EthAccount, StorageProof,
 	block_number_to_id
 };
 use v1::metadata::Metadata;
@@ -78,6 +79,10 @@ pub struct EthClientOptions {
 	pub allow_experimental_rpcs: bool,
 	/// flag for ancient block sync
 	pub no_ancient_blocks: bool,
+	/// Maximal number of logs returned by a query which doesn't specify a `limit`.
+	pub max_logs: Option<usize>,
+	/// Maximal number of blocks a log filter given by block numbers may span.
+	pub max_block_range: Option<u64>,
 }
 
 impl EthClientOptions {
@@ -100,6 +105,8 @@ impl Default for EthClientOptions {
 			allow_missing_blocks: false,
 			allow_experimental_rpcs: false,
 			no_ancient_blocks: false,
+			max_logs: None,
+			max_block_range: None,
 		}
 	}
 }
@@ -156,15 +163,60 @@ enum PendingTransactionId {
 	Location(PendingOrBlock, usize)
 }
 
-pub fn base_logs<C, M, T: StateInfo + 'static> (client: &C, miner: &M, filter: Filter) -> BoxFuture<Vec<Log>> where
+pub fn base_logs<C, M, T: StateInfo + 'static> (
+	client: &C,
+	miner: &M,
+	filter: Filter,
+	max_logs: Option<usize>,
+	max_block_range: Option<u64>,
+) -> BoxFuture<Vec<Log>> where
 	C: miner::BlockChainClient + BlockChainClient + StateClient<State=T> + Call<State=T>,
 	M: MinerService<State=T> {
-	let include_pending = filter.to_block == Some(BlockNumber::Pending);
+	// logs of the pending block are newer than any cursor, so they only belong to the first page.
+	let include_pending = filter.to_block == Some(BlockNumber::Pending) && filter.cursor.is_none();
+	let cursor = filter.cursor;
 	let filter: EthcoreFilter = match filter.try_into() {
 		Ok(value) => value,
 		Err(err) => return Box::new(future::err(err)),
 	};
-	let mut logs = match client.logs(filter.clone()) {
+
+	// filters given by block hash only ever touch the blocks named, so only numbered ranges are bounded.
+	if let Some(max_block_range) = max_block_range {
+		let is_hash = |id: &BlockId| if let BlockId::Hash(_) = id { true } else { false };
+		if !is_hash(&filter.from_block) && !is_hash(&filter.to_block) {
+			let from = BlockChainClient::block_number(client, filter.from_block);
+			let to = BlockChainClient::block_number(client, filter.to_block);
+			if let (Some(from), Some(to)) = (from, to) {
+				if to.saturating_sub(from) >= max_block_range {
+					return Box::new(future::err(errors::filter_block_range_too_wide(max_block_range)));
+				}
+			}
+		}
+	}
+
+	if let Some(horizon) = client.history_horizon() {
+		if let Some(from) = BlockChainClient::block_number(client, filter.from_block) {
+			if from < horizon {
+				return Box::new(future::err(errors::filter_block_before_horizon(horizon)));
+			}
+		}
+	}
+
+	// fetch enough logs to serve the requested page, or one more than
+	// allowed for unlimited queries so that we can tell when to bail out.
+	let mut client_filter = filter.clone();
+	client_filter.limit = match (filter.limit, max_logs) {
+		(Some(limit), _) => Some(limit),
+		(None, Some(max_logs)) => Some(max_logs.saturating_add(1)),
+		(None, None) => None,
+	};
+
+	let logs = match cursor {
+		Some(cursor) => logs_before(client, client_filter, cursor),
+		None => client.logs(client_filter),
+	};
+
+	let mut logs = match logs {
 		Ok(logs) => logs
 			.into_iter()
 			.map(From::from)
@@ -180,7 +232,59 @@ pub fn base_logs<C, M, T: StateInfo + 'static> (client: &C, miner: &M, filter: F
 
 	let logs = limit_logs(logs, filter.limit);
 
-	Box::new(future::ok(logs))
+	match (filter.limit, max_logs) {
+		(None, Some(max_logs)) if logs.len() > max_logs =>
+			Box::new(future::err(errors::request_rejected_param_limit(max_logs as u64, "logs"))),
+		_ => Box::new(future::ok(logs)),
+	}
+}
+
+/// Fetches the logs matching `filter` which precede `cursor`. The cursor's own block is
+/// queried on its own, so that `filter.limit` keeps bounding the rest of the range.
+fn logs_before<C: BlockChainClient>(
+	client: &C,
+	filter: EthcoreFilter,
+	cursor: LogCursor,
+) -> ::std::result::Result<Vec<LocalizedLogEntry>, BlockId> {
+	let cursor_block = cursor.block_number.as_u64();
+	let cursor_index = cursor.log_index.as_u64() as usize;
+	let from = client.block_number(filter.from_block).ok_or(filter.from_block)?;
+	let to = client.block_number(filter.to_block).ok_or(filter.to_block)?;
+
+	if cursor_block < from {
+		return Ok(Vec::new());
+	}
+
+	if cursor_block > to {
+		return client.logs(filter);
+	}
+
+	let mut logs = if cursor_block > from {
+		client.logs(EthcoreFilter {
+			to_block: BlockId::Number(cursor_block - 1),
+			..filter.clone()
+		})?
+	} else {
+		Vec::new()
+	};
+
+	// keep the original id of a boundary block, it may be referred to by hash.
+	let cursor_id = if cursor_block == to {
+		filter.to_block
+	} else if cursor_block == from {
+		filter.from_block
+	} else {
+		BlockId::Number(cursor_block)
+	};
+	let current = client.logs(EthcoreFilter {
+		from_block: cursor_id,
+		to_block: cursor_id,
+		limit: None,
+		..filter
+	})?;
+	logs.extend(current.into_iter().filter(|log| log.log_index < cursor_index));
+
+	Ok(logs)
 }
 
 impl<C, SN: ?Sized, S: ?Sized, M, EM, T> EthClient<C, SN, S, M, EM> where
@@ -870,7 +974,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 	}
 
 	fn logs(&self, filter: Filter) -> BoxFuture<Vec<Log>> {
-		base_logs(&*self.client, &*self.miner, filter)
+		base_logs(&*self.client, &*self.miner, filter, self.options.max_logs, self.options.max_block_range)
 	}
 
 	fn work(&self, no_new_work_timeout: Option<u64>) -> Result<Work> {
@@ -1026,7 +1130,7 @@ impl<C, SN: ?Sized, S: ?Sized, M, EM, T: StateInfo + 'static> Eth for EthClient<
 		};
 
 		Box::new(future::done(self.client.estimate_gas(&signed, &state, &header)
-			.map_err(errors::call)
+			.map_err(errors::estimate_gas)
 		))
 	}
 
@@ -23,12 +23,26 @@ use v1::traits::Web3;
 use v1::types::Bytes;
 
 /// Web3 rpc implementation.
-#[derive(Default)]
-pub struct Web3Client;
+pub struct Web3Client {
+	version: String,
+}
+
+impl Web3Client {
+	/// Creates new Web3Client reporting the given version string.
+	pub fn new(version: String) -> Self {
+		Web3Client { version }
+	}
+}
+
+impl Default for Web3Client {
+	fn default() -> Self {
+		Web3Client::new(version())
+	}
+}
 
 impl Web3 for Web3Client {
 	fn client_version(&self) -> Result<String> {
-		Ok(version().to_owned().replacen("/", "//", 1))
+		Ok(self.version.replacen("/", "//", 1))
 	}
 
 	fn sha3(&self, data: Bytes) -> Result<H256> {
@@ -16,7 +16,7 @@
 
 //! rpc integration tests.
 
-use std::{env, sync::Arc};
+use std::{env, sync::Arc, time::{Duration, Instant}};
 
 use accounts::AccountProvider;
 use client_traits::{BlockChainClient, ChainInfo, ImportBlock};
@@ -144,7 +144,9 @@ impl EthTester {
 				gas_price_percentile: 50,
 				allow_experimental_rpcs: true,
 				allow_missing_blocks: false,
-				no_ancient_blocks: false
+				no_ancient_blocks: false,
+				max_logs: None,
+				max_block_range: None,
 			},
 		);
 
@@ -509,6 +511,77 @@ fn starting_nonce_test() {
 	assert_eq!(r#"{"jsonrpc":"2.0","result":"0x100","id":15}"#, &sample);
 }
 
+// a frontier-like test with a contract that loops forever (JUMPDEST PUSH1 0 JUMP) at 0x1009.
+const LOOPING_CONTRACT_SPEC: &'static [u8] = br#"{
+	"name": "Frontier (Test)",
+	"engine": {
+		"Ethash": {
+			"params": {
+				"minimumDifficulty": "0x020000",
+				"difficultyBoundDivisor": "0x0800",
+				"blockReward": "0x4563918244F40000",
+				"durationLimit": "0x0d",
+				"homesteadTransition": "0xffffffffffffffff",
+				"daoHardforkTransition": "0xffffffffffffffff",
+				"daoHardforkBeneficiary": "0x0000000000000000000000000000000000000000",
+				"daoHardforkAccounts": []
+			}
+		}
+	},
+	"params": {
+		"gasLimitBoundDivisor": "0x0400",
+		"registrar" : "0xc6d9d2cd449a754c494264e1809c50e34d64562b",
+		"accountStartNonce": "0x00",
+		"maximumExtraDataSize": "0x20",
+		"minGasLimit": "0x50000",
+		"networkID" : "0x1"
+	},
+	"genesis": {
+		"seal": {
+			"ethereum": {
+				"nonce": "0x0000000000000042",
+				"mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000"
+			}
+		},
+		"difficulty": "0x400000000",
+		"author": "0x0000000000000000000000000000000000000000",
+		"timestamp": "0x00",
+		"parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+		"extraData": "0x11bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+		"gasLimit": "0x50000"
+	},
+	"accounts": {
+		"0000000000000000000000000000000000000001": { "builtin": { "name": "ecrecover", "pricing": { "linear": { "base": 3000, "word": 0 } } } },
+		"0000000000000000000000000000000000000002": { "builtin": { "name": "sha256", "pricing": { "linear": { "base": 60, "word": 12 } } } },
+		"0000000000000000000000000000000000000003": { "builtin": { "name": "ripemd160", "pricing": { "linear": { "base": 600, "word": 120 } } } },
+		"0000000000000000000000000000000000000004": { "builtin": { "name": "identity", "pricing": { "linear": { "base": 15, "word": 3 } } } },
+		"faa34835af5c2ea724333018a515fbb7d5bc0b33": { "balance": "10000000000000", "nonce": "0" },
+		"0000000000000000000000000000000000001009": { "balance": "0", "code": "0x5b600056" }
+	}
+}
+"#;
+
+#[test]
+fn eth_call_times_out() {
+	let mut config = ClientConfig::default();
+	config.call_timeout = Some(Duration::from_millis(50));
+	let tester = EthTester::from_spec_conf(Spec::load(&env::temp_dir(), LOOPING_CONTRACT_SPEC).expect("invalid chain spec"), config);
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_call",
+		"params": [{
+			"to": "0x0000000000000000000000000000000000001009"
+		}, "latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32018,"message":"Call execution timed out."},"id":1}"#;
+
+	let started = Instant::now();
+	assert_eq!(tester.handler.handle_request_sync(request).unwrap(), response);
+	assert!(started.elapsed() < Duration::from_secs(5));
+}
+
 register_test!(eth_transaction_count_1, verify_transaction_counts, "BlockchainTests/ValidBlocks/bcWalletTest/wallet2outOf3txs");
 register_test!(eth_transaction_count_2, verify_transaction_counts, "BlockchainTests/ValidBlocks/bcTotalDifficultyTest/sideChainWithMoreTransactions");
 register_test!(eth_transaction_count_3, verify_transaction_counts, "BlockchainTests/ValidBlocks/bcGasPricerTest/RPC_API_Test");
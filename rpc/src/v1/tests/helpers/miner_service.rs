@@ -28,7 +28,7 @@ use ethcore::miner::{self, MinerService, AuthoringParams, FilterOptions};
 use ethcore::test_helpers::TestState;
 use ethereum_types::{H256, U256, Address};
 use miner::pool::local_transactions::Status as LocalTransactionStatus;
-use miner::pool::{verifier, VerifiedTransaction, QueueStatus};
+use miner::pool::{verifier, VerifiedTransaction, QueueStatus, ImportOutcome};
 use parking_lot::{RwLock, Mutex};
 use txpool;
 use types::{
@@ -147,7 +147,7 @@ impl MinerService for TestMinerService {
 
 	/// Imports transactions to transaction queue.
 	fn import_external_transactions<C: Nonce + Sync>(&self, chain: &C, transactions: Vec<UnverifiedTransaction>)
-		-> Vec<Result<(), transaction::Error>>
+		-> ImportOutcome
 	{
 		// lets assume that all txs are valid
 		let transactions: Vec<_> = transactions.into_iter().map(|tx| SignedTransaction::new(tx).unwrap()).collect();
@@ -158,22 +158,22 @@ impl MinerService for TestMinerService {
 			self.next_nonces.write().insert(sender, nonce);
 		}
 
-		transactions
-			.iter()
-			.map(|_| Ok(()))
-			.collect()
+		ImportOutcome {
+			results: transactions.iter().map(|_| Ok(())).collect(),
+			evicted: Vec::new(),
+		}
 	}
 
 	/// Imports transactions to transaction queue.
 	fn import_own_transaction<C: Nonce + Sync>(&self, _chain: &C, _pending: PendingTransaction)
-		-> Result<(), transaction::Error> {
+		-> Result<Vec<H256>, transaction::Error> {
 		// this function is no longer called directly from RPC
 		unimplemented!();
 	}
 
 	/// Imports transactions to queue - treats as local based on trusted flag, config, and tx source
 	fn import_claimed_local_transaction<C: Nonce + Sync>(&self, chain: &C, pending: PendingTransaction, _trusted: bool)
-		-> Result<(), transaction::Error> {
+		-> Result<Vec<H256>, transaction::Error> {
 
 		// keep the pending nonces up to date
 		let sender = pending.transaction.sender();
@@ -183,7 +183,7 @@ impl MinerService for TestMinerService {
 		// lets assume that all txs are valid
 		self.imported_transactions.lock().push(pending.transaction);
 
-		Ok(())
+		Ok(Vec::new())
 	}
 
 	/// Called when blocks are imported to chain, updates transactions queue.
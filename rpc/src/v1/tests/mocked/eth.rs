@@ -29,6 +29,7 @@ use miner::external::ExternalMiner;
 use parity_runtime::Runtime;
 use parking_lot::Mutex;
 use rlp;
+use vm;
 use rustc_hex::{FromHex, ToHex};
 use sync::SyncState;
 use types::{
@@ -257,6 +258,97 @@ fn rpc_eth_logs_error() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_logs_pagination() {
+	use serde_json::{self, Value};
+
+	let tester = EthTester::new_with_options(EthClientOptions::with(|options| {
+		options.max_logs = Some(10);
+	}));
+	let log = |block_number, log_index| LocalizedLogEntry {
+		block_number,
+		block_hash: H256::zero(),
+		entry: LogEntry {
+			address: Address::zero(),
+			topics: vec![],
+			data: vec![1,2,3],
+		},
+		transaction_index: 0,
+		transaction_log_index: log_index,
+		transaction_hash: H256::zero(),
+		log_index,
+	};
+	tester.client.add_blocks(2, EachBlockWith::Nothing);
+	tester.client.set_logs((0..25).map(|i| log(1, i)).collect());
+
+	let positions = |request: &str| -> Vec<(String, String)> {
+		let response: Value = serde_json::from_str(&tester.io.handle_request_sync(request).unwrap()).unwrap();
+		response["result"].as_array().unwrap().iter()
+			.map(|log| (log["blockNumber"].as_str().unwrap().to_owned(), log["logIndex"].as_str().unwrap().to_owned()))
+			.collect()
+	};
+	let expected = |range: ::std::ops::Range<usize>| range.map(|i| ("0x1".to_owned(), format!("0x{:x}", i))).collect::<Vec<_>>();
+
+	// without a limit the query exceeds `max_logs`
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32041,"message":"Requested data size exceeds limit of 10 logs."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	// first page returns the most recent logs
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0","limit":10}], "id": 1}"#;
+	assert_eq!(positions(request), expected(15..25));
+
+	// a new block arrives between page fetches
+	tester.client.add_blocks(1, EachBlockWith::Nothing);
+	tester.client.set_logs((0..25).map(|i| log(1, i)).chain((0..5).map(|i| log(3, i))).collect());
+
+	// continue from the oldest log of the previous page, unaffected by the new block
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0","limit":10,"cursor":{"blockNumber":"0x1","logIndex":"0xf"}}], "id": 1}"#;
+	assert_eq!(positions(request), expected(5..15));
+
+	// the last page is shorter
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0","limit":10,"cursor":{"blockNumber":"0x1","logIndex":"0x5"}}], "id": 1}"#;
+	assert_eq!(positions(request), expected(0..5));
+
+	// and past the end there is nothing left
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0","limit":10,"cursor":{"blockNumber":"0x1","logIndex":"0x0"}}], "id": 1}"#;
+	assert!(positions(request).is_empty());
+}
+
+#[test]
+fn rpc_eth_logs_block_range_limit() {
+	let tester = EthTester::new_with_options(EthClientOptions::with(|options| {
+		options.max_block_range = Some(10);
+	}));
+	tester.client.add_blocks(20, EachBlockWith::Nothing);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x0","toBlock":"latest"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32041,"message":"Filter spans more than 10 blocks. Narrow the range with fromBlock/toBlock or query a single blockHash."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x5","toBlock":"0xe"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"blockHash":"0x0000000000000000000000000000000000000000000000000000000000000000"}], "id": 1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_eth_logs_before_pruning_horizon() {
+	let tester = EthTester::default();
+	tester.client.add_blocks(10, EachBlockWith::Nothing);
+	tester.client.set_pruning_window(Some(4));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x6","toBlock":"latest"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32000,"message":"Filter starts before the pruning horizon #7. Older blocks are no longer available on this node."},"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+
+	let request = r#"{"jsonrpc": "2.0", "method": "eth_getLogs", "params": [{"fromBlock":"0x7","toBlock":"latest"}], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":[],"id":1}"#;
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_logs_filter() {
 	let tester = EthTester::default();
@@ -423,6 +515,38 @@ fn rpc_eth_accounts() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_batch_request() {
+	let tester = EthTester::default();
+	let address = tester.accounts_provider.new_account(&"".into()).unwrap();
+	tester.client.set_balance(Address::from_low_u64_be(1), U256::from(5));
+
+	let request = r#"[
+		{"jsonrpc": "2.0", "method": "eth_accounts", "params": [], "id": 1},
+		{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "latest"], "id": 2}
+	]"#;
+	let response = r#"[{"jsonrpc":"2.0","result":[""#.to_owned()
+		+ &format!("0x{:x}", address)
+		+ r#""],"id":1},{"jsonrpc":"2.0","result":"0x5","id":2}]"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response));
+}
+
+#[test]
+fn rpc_eth_batch_request_with_malformed_member() {
+	let tester = EthTester::default();
+	tester.client.set_balance(Address::from_low_u64_be(1), U256::from(5));
+
+	let request = r#"[
+		{"jsonrpc": "2.0", "method": "eth_getBalance", "params": ["0x0000000000000000000000000000000000000001", "latest"], "id": 1},
+		{"jsonrpc": "2.0", "params": [], "id": 2},
+		{"jsonrpc": "2.0", "method": "eth_blockNumber", "params": [], "id": 3}
+	]"#;
+	let response = r#"[{"jsonrpc":"2.0","result":"0x5","id":1},{"jsonrpc":"2.0","error":{"code":-32600,"message":"Invalid request"},"id":2},{"jsonrpc":"2.0","result":"0x0","id":3}]"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_block_number() {
 	let tester = EthTester::default();
@@ -642,6 +766,7 @@ fn rpc_eth_call_latest() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -678,6 +803,7 @@ fn rpc_eth_call_pending() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -715,6 +841,7 @@ fn rpc_eth_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -751,6 +878,7 @@ fn rpc_eth_call_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -786,6 +914,7 @@ fn rpc_eth_estimate_gas() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -822,6 +951,7 @@ fn rpc_eth_estimate_gas_pending() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -859,6 +989,7 @@ fn rpc_eth_estimate_gas_default_block() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 
 	let request = r#"{
@@ -879,6 +1010,60 @@ fn rpc_eth_estimate_gas_default_block() {
 	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
 }
 
+#[test]
+fn rpc_eth_estimate_gas_value_transfer() {
+	let tester = EthTester::default();
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_estimateGas",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"value": "0x9184e72a"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"0x5208","id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
+#[test]
+fn rpc_eth_estimate_gas_always_reverts() {
+	let tester = EthTester::default();
+	tester.client.set_execution_result(Ok(Executed {
+		exception: Some(vm::Error::Reverted),
+		gas: U256::zero(),
+		gas_used: U256::from(0xff30),
+		refunded: U256::zero(),
+		cumulative_gas_used: U256::zero(),
+		logs: vec![],
+		contracts_created: vec![],
+		output: vec![],
+		trace: vec![],
+		vm_trace: None,
+		state_diff: None,
+		deepest_failure: None,
+	}));
+
+	let request = r#"{
+		"jsonrpc": "2.0",
+		"method": "eth_estimateGas",
+		"params": [{
+			"from": "0xb60e8dd61c5d32be8058bb8eb970870f07233155",
+			"to": "0xd46e8dd67c5d32be8058bb8eb970870f07244567",
+			"data": "0xd46e8dd6"
+		},
+		"latest"],
+		"id": 1
+	}"#;
+	let response = r#"{"jsonrpc":"2.0","error":{"code":-32016,"message":"Gas estimation failed: the transaction fails even with the highest gas allowance the node will try.","data":"Reverted"},"id":1}"#;
+
+	assert_eq!(tester.io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_eth_send_raw_transaction_error() {
 	let tester = EthTester::default();
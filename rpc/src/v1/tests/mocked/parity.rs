@@ -495,6 +495,7 @@ fn rpc_parity_call() {
 		trace: vec![],
 		vm_trace: None,
 		state_diff: None,
+		deepest_failure: None,
 	}));
 	let io = deps.default_client();
 
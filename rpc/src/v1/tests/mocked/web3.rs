@@ -32,6 +32,18 @@ fn rpc_web3_version() {
 	assert_eq!(io.handle_request_sync(request), Some(response));
 }
 
+#[test]
+fn rpc_web3_version_configured() {
+	let web3 = Web3Client::new("Parity-Ethereum/v9.9.9-test/x86_64-linux-gnu/rustc1.40.0".into()).to_delegate();
+	let mut io = IoHandler::new();
+	io.extend_with(web3);
+
+	let request = r#"{"jsonrpc": "2.0", "method": "web3_clientVersion", "params": [], "id": 1}"#;
+	let response = r#"{"jsonrpc":"2.0","result":"Parity-Ethereum//v9.9.9-test/x86_64-linux-gnu/rustc1.40.0","id":1}"#;
+
+	assert_eq!(io.handle_request_sync(request), Some(response.to_owned()));
+}
+
 #[test]
 fn rpc_web3_sha3() {
 	let web3 = Web3Client::default().to_delegate();
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::{H160, H256};
+use ethereum_types::{H160, H256, U64};
 use jsonrpc_core::{Error as RpcError};
 use serde::de::{Error, DeserializeOwned};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -73,6 +73,21 @@ pub struct Filter {
 	pub topics: Option<Vec<Topic>>,
 	/// Limit
 	pub limit: Option<usize>,
+	/// Only return logs which precede this position. Together with `limit`
+	/// this pages backwards through large results: pass the position of the
+	/// oldest log of the previous page to fetch the next one.
+	pub cursor: Option<LogCursor>,
+}
+
+/// Position of a log in the chain.
+#[derive(Debug, PartialEq, Clone, Copy, Deserialize, Serialize, Eq, Hash)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct LogCursor {
+	/// Number of the block containing the log
+	pub block_number: U64,
+	/// Index of the log within the block
+	pub log_index: U64,
 }
 
 impl Filter {
@@ -177,6 +192,7 @@ mod tests {
 			address: None,
 			topics: None,
 			limit: None,
+			cursor: None,
 		});
 	}
 
@@ -193,6 +209,7 @@ mod tests {
 				VariadicValue::Null,
 			]),
 			limit: None,
+			cursor: None,
 		};
 
 		let eth_filter: EthFilter = filter.try_into().unwrap();
@@ -61,7 +61,7 @@ pub use self::confirmations::{
 };
 pub use self::consensus_status::*;
 pub use self::derivation::{DeriveHash, DeriveHierarchical, Derive};
-pub use self::filter::{Filter, FilterChanges};
+pub use self::filter::{Filter, FilterChanges, LogCursor};
 pub use self::histogram::Histogram;
 pub use self::index::Index;
 pub use self::log::Log;
@@ -132,6 +132,7 @@ mod tests {
 			address: None,
 			topics: None,
 			limit: None,
+			cursor: None,
 		}));
 		assert_eq!(logs2, Params::Logs(Filter {
 			from_block: None,
@@ -140,6 +141,7 @@ mod tests {
 			address: None,
 			topics: None,
 			limit: Some(10),
+			cursor: None,
 		}));
 		assert_eq!(logs3, Params::Logs(Filter {
 			from_block: None,
@@ -150,6 +152,7 @@ mod tests {
 				VariadicValue::Single("000000000000000000000000a94f5374fce5edbc8e2a8697c15331677e6ebf0b".parse().unwrap()
 			)]),
 			limit: None,
+			cursor: None,
 		}));
 	}
 
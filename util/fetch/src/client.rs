@@ -19,7 +19,10 @@ use futures::sync::{mpsc, oneshot};
 use futures::{self, Future, Async, Sink, Stream};
 use hyper::header::{self, HeaderMap, HeaderValue, IntoHeaderName};
 use hyper::{self, Method, StatusCode};
-use hyper_rustls;
+use hyper::client::HttpConnector;
+use hyper_rustls::{self, HttpsConnector};
+use ring::digest;
+use rustls::{self, Certificate, ClientConfig, RootCertStore, ServerCertVerified, ServerCertVerifier, TLSError};
 use std;
 use std::cmp::min;
 use std::sync::Arc;
@@ -31,10 +34,12 @@ use std::{io, fmt};
 use tokio::{self, util::FutureExt};
 use url::{self, Url};
 use bytes::Bytes;
+use webpki::DNSNameRef;
 
 const MAX_SIZE: usize = 64 * 1024 * 1024;
 const MAX_SECS: Duration = Duration::from_secs(5);
 const MAX_REDR: usize = 5;
+const PIN_MISMATCH: &str = "server certificate does not match the pinned SHA-256 fingerprint";
 
 /// A handle to abort requests.
 ///
@@ -167,10 +172,19 @@ impl Drop for Client {
 impl Client {
 	/// Create a new fetch client.
 	pub fn new(num_dns_threads: usize) -> Result<Self, Error> {
+		Client::with_pin(num_dns_threads, None)
+	}
+
+	/// Create a new fetch client, optionally pinning the server certificate.
+	///
+	/// With `pinned_sha256` set, HTTPS connections are only accepted when the SHA-256 of the
+	/// server's leaf certificate matches it, and fail with `Error::PinMismatch` otherwise.
+	/// The pin replaces the root store, so it should only be used to fetch from a known host.
+	pub fn with_pin(num_dns_threads: usize, pinned_sha256: Option<[u8; 32]>) -> Result<Self, Error> {
 		let (tx_start, rx_start) = std::sync::mpsc::sync_channel(1);
 		let (tx_proto, rx_proto) = mpsc::channel(64);
 
-		Client::background_thread(tx_start, rx_proto, num_dns_threads)?;
+		Client::background_thread(tx_start, rx_proto, num_dns_threads, pinned_sha256)?;
 
 		match rx_start.recv_timeout(Duration::from_secs(10)) {
 			Err(RecvTimeoutError::Timeout) => {
@@ -194,15 +208,23 @@ impl Client {
 		})
 	}
 
-	fn background_thread(tx_start: TxStartup, rx_proto: mpsc::Receiver<ChanItem>, num_dns_threads: usize) -> io::Result<thread::JoinHandle<()>> {
+	fn background_thread(
+		tx_start: TxStartup,
+		rx_proto: mpsc::Receiver<ChanItem>,
+		num_dns_threads: usize,
+		pinned_sha256: Option<[u8; 32]>,
+	) -> io::Result<thread::JoinHandle<()>> {
 		thread::Builder::new().name("fetch".into()).spawn(move || {
 			let mut runtime = match tokio::runtime::current_thread::Runtime::new() {
 				Ok(c) => c,
 				Err(e) => return tx_start.send(Err(e)).unwrap_or(())
 			};
 
-			let hyper = hyper::Client::builder()
-				.build(hyper_rustls::HttpsConnector::new(num_dns_threads));
+			let connector = match pinned_sha256 {
+				Some(pin) => pinned_connector(num_dns_threads, pin),
+				None => HttpsConnector::new(num_dns_threads),
+			};
+			let hyper = hyper::Client::builder().build(connector);
 
 			let future = rx_proto.take_while(|item| Ok(item.is_some()))
 				.map(|item| item.expect("`take_while` is only passing on channel items != None; qed"))
@@ -319,6 +341,34 @@ impl Fetch for Client {
 	}
 }
 
+// HTTPS connector accepting only the server certificate with the given SHA-256.
+fn pinned_connector(num_dns_threads: usize, pin: [u8; 32]) -> HttpsConnector<HttpConnector> {
+	let mut http = HttpConnector::new(num_dns_threads);
+	http.enforce_http(false);
+	let mut config = ClientConfig::new();
+	config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+	config.dangerous().set_certificate_verifier(Arc::new(PinnedCertVerifier(pin)));
+	HttpsConnector::from((http, config))
+}
+
+// Checks the server's leaf certificate against a pinned SHA-256 fingerprint.
+struct PinnedCertVerifier([u8; 32]);
+
+impl ServerCertVerifier for PinnedCertVerifier {
+	fn verify_server_cert(
+		&self,
+		_roots: &RootCertStore,
+		presented_certs: &[Certificate],
+		_dns_name: DNSNameRef,
+		_ocsp_response: &[u8],
+	) -> Result<ServerCertVerified, TLSError> {
+		match presented_certs.first() {
+			Some(leaf) if digest::digest(&digest::SHA256, &leaf.0).as_ref() == &self.0[..] => Ok(ServerCertVerified::assertion()),
+			_ => Err(TLSError::General(PIN_MISMATCH.into())),
+		}
+	}
+}
+
 // Extract redirect location from response. The second return value indicate whether the original method should be preserved.
 fn redirect_location(u: Url, r: &Response) -> Option<(Url, bool)> {
 	let preserve_method = match r.status() {
@@ -585,6 +635,8 @@ pub enum Error {
 	SizeLimit,
 	/// The background processing thread does not run.
 	BackgroundThreadDead,
+	/// The server certificate does not match the pinned fingerprint.
+	PinMismatch,
 }
 
 impl fmt::Display for Error {
@@ -602,6 +654,7 @@ impl fmt::Display for Error {
 			Error::TokioTimer(ref e) => write!(fmt, "tokio timer error: {:?}", e),
 			Error::Timeout => write!(fmt, "request timed out"),
 			Error::SizeLimit => write!(fmt, "size limit reached"),
+			Error::PinMismatch => write!(fmt, "{}", PIN_MISMATCH),
 		}
 	}
 }
@@ -613,8 +666,29 @@ impl ::std::error::Error for Error {
 
 impl From<hyper::Error> for Error {
 	fn from(e: hyper::Error) -> Self {
-		Error::Hyper(e)
+		if is_pin_mismatch(&e) {
+			Error::PinMismatch
+		} else {
+			Error::Hyper(e)
+		}
+	}
+}
+
+// Whether the error was caused by `PinnedCertVerifier` rejecting the server certificate.
+// The TLS error reaches us wrapped in the `io::Error` of the connector, which doesn't expose
+// its custom error as a `source`, so that one is unwrapped explicitly.
+fn is_pin_mismatch(err: &(dyn std::error::Error + 'static)) -> bool {
+	if let Some(TLSError::General(ref msg)) = err.downcast_ref::<TLSError>() {
+		if msg == PIN_MISMATCH {
+			return true;
+		}
 	}
+	if let Some(inner) = err.downcast_ref::<io::Error>().and_then(|e| e.get_ref()) {
+		if is_pin_mismatch(inner) {
+			return true;
+		}
+	}
+	err.source().map_or(false, is_pin_mismatch)
 }
 
 impl From<hyper::header::ToStrError> for Error {
@@ -865,6 +939,53 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn it_should_fetch_with_matching_pin() {
+		let addr = run_tls_server();
+		let pin = digest::digest(&digest::SHA256, TEST_CERT);
+		let mut expected = [0u8; 32];
+		expected.copy_from_slice(pin.as_ref());
+		let client = Client::with_pin(4, Some(expected)).unwrap();
+		let mut runtime = Runtime::new().unwrap();
+
+		let future = client.get(&format!("https://localhost:{}/", addr.port()), Abort::default())
+			.map(|resp| {
+				assert!(resp.is_success());
+				resp
+			})
+			.map(|resp| resp.concat2())
+			.flatten()
+			.map(|body| assert_eq!(&body[..], b"123"))
+			.map_err(|err| panic!(err));
+
+		runtime.block_on(future).unwrap();
+	}
+
+	#[test]
+	fn it_should_detect_pin_mismatch_by_error_type() {
+		let mismatch = io::Error::new(io::ErrorKind::InvalidData, TLSError::General(PIN_MISMATCH.into()));
+		assert!(is_pin_mismatch(&mismatch));
+
+		let other_tls = io::Error::new(io::ErrorKind::InvalidData, TLSError::General("other".into()));
+		assert!(!is_pin_mismatch(&other_tls));
+
+		// the message alone is not enough
+		let message = io::Error::new(io::ErrorKind::Other, PIN_MISMATCH);
+		assert!(!is_pin_mismatch(&message));
+	}
+
+	#[test]
+	fn it_should_not_fetch_with_mismatched_pin() {
+		let addr = run_tls_server();
+		let client = Client::with_pin(4, Some([0u8; 32])).unwrap();
+		let mut runtime = Runtime::new().unwrap();
+
+		match runtime.block_on(client.get(&format!("https://localhost:{}/", addr.port()), Abort::default())) {
+			Err(Error::PinMismatch) => {},
+			other => panic!("Expected `Error::PinMismatch`, got: {:?}", other),
+		}
+	}
+
 	struct TestServer;
 
 	impl Service for TestServer {
@@ -951,4 +1072,38 @@ mod test {
 			self.1.take().unwrap().send(()).unwrap();
 		}
 	}
+
+	// Self-signed certificate for `localhost` and its PKCS#8 key.
+	const TEST_CERT: &[u8] = include_bytes!("../res/localhost.crt.der");
+	const TEST_KEY: &[u8] = include_bytes!("../res/localhost.key.der");
+
+	// Answers a single HTTPS request with "123", using the test certificate.
+	fn run_tls_server() -> SocketAddr {
+		use std::io::Write;
+
+		let mut config = rustls::ServerConfig::new(rustls::NoClientAuth::new());
+		config.set_single_cert(vec![Certificate(TEST_CERT.to_vec())], rustls::PrivateKey(TEST_KEY.to_vec())).unwrap();
+		let config = Arc::new(config);
+
+		let listener = std::net::TcpListener::bind(ADDRESS).unwrap();
+		let addr = listener.local_addr().unwrap();
+		thread::spawn(move || {
+			let (mut socket, _) = listener.accept().unwrap();
+			let mut session = rustls::ServerSession::new(&config);
+			let mut stream = rustls::Stream::new(&mut session, &mut socket);
+
+			let mut request = Vec::new();
+			let mut buf = [0; 1024];
+			while !request.ends_with(b"\r\n\r\n") {
+				match stream.read(&mut buf) {
+					Ok(0) | Err(_) => return,
+					Ok(n) => request.extend_from_slice(&buf[..n]),
+				}
+			}
+			let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 3\r\nConnection: close\r\n\r\n123");
+			let _ = stream.flush();
+		});
+
+		addr
+	}
 }
@@ -27,10 +27,13 @@ extern crate futures;
 extern crate hyper;
 extern crate hyper_rustls;
 extern crate http;
+extern crate ring;
+extern crate rustls;
 
 extern crate tokio;
 extern crate url;
 extern crate bytes;
+extern crate webpki;
 
 /// Fetch client implementation.
 pub mod client;
@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
+use std::cmp::{max, min};
 use std::collections::VecDeque;
 use std::io::{self, Cursor, Read, Write};
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use bytes::{Buf, BufMut};
 use parity_crypto::aes::{AesCtr256, AesEcb256};
@@ -46,12 +47,54 @@ pub const MAX_PAYLOAD_SIZE: usize = (1 << 24) - 1; // 16Mb
 /// This should be lower than MAX_PAYLOAD_SIZE
 pub const PAYLOAD_SOFT_LIMIT: usize = (1 << 22) - 1; // 4Mb
 
+/// Default limit for the amount of data waiting to be written to a single peer.
+pub const MAX_QUEUED_BYTES: usize = 1 << 26; // 64Mb
+
 pub trait GenericSocket : Read + Write {
 }
 
 impl GenericSocket for TcpStream {
 }
 
+/// Token bucket limiting the rate at which a connection writes to its socket.
+#[derive(Clone)]
+struct OutboundLimiter {
+	/// Bytes allowed per second, also used as the burst size.
+	rate: u32,
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl OutboundLimiter {
+	fn new(rate: u32) -> Self {
+		OutboundLimiter {
+			rate,
+			tokens: rate as f64,
+			last_refill: Instant::now(),
+		}
+	}
+
+	/// Number of bytes which may be written at `now`, given `pending` bytes are waiting to be written.
+	/// Returns 0 until the budget covers either all of them or a full second worth of bytes,
+	/// so that a throttled connection isn't woken up for every few bytes earned.
+	fn allowance(&mut self, now: Instant, pending: usize) -> usize {
+		let elapsed = now.checked_duration_since(self.last_refill).unwrap_or_default();
+		self.last_refill = max(self.last_refill, now);
+		self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(self.rate as f64);
+		let tokens = self.tokens as usize;
+		if tokens >= min(pending, self.rate as usize) {
+			tokens
+		} else {
+			0
+		}
+	}
+
+	/// Charge `size` written bytes against the budget.
+	fn consume(&mut self, size: usize) {
+		self.tokens = (self.tokens - size as f64).max(0.0);
+	}
+}
+
 pub struct GenericConnection<Socket: GenericSocket> {
 	/// Connection id (token)
 	pub token: StreamToken,
@@ -63,6 +106,20 @@ pub struct GenericConnection<Socket: GenericSocket> {
 	rec_size: usize,
 	/// Send out packets FIFO
 	send_queue: VecDeque<Cursor<Bytes>>,
+	/// Number of bytes in the send queue which are yet to be written
+	queued_bytes: usize,
+	/// Maximum total size of the packets in the send queue
+	max_queued_bytes: usize,
+	/// Set when a packet was dropped because the send queue was full
+	overflowed: bool,
+	/// Total number of bytes read from the socket
+	bytes_read: u64,
+	/// Total number of bytes written to the socket
+	bytes_written: u64,
+	/// Caps the rate at which bytes are written to the socket
+	outbound_limiter: Option<OutboundLimiter>,
+	/// Set when a write was deferred because the outbound byte rate was exceeded
+	throttled: bool,
 	/// Event flags this connection expects
 	interest: Ready,
 	/// Registered flag
@@ -89,6 +146,7 @@ impl<Socket: GenericSocket> GenericConnection<Socket> {
 			match sock_ref.take(max as u64).try_read(unsafe { self.rec_buf.bytes_mut() }) {
 				Ok(Some(size)) if size != 0  => {
 					unsafe { self.rec_buf.advance_mut(size); }
+					self.bytes_read += size as u64;
 					trace!(target:"network", "{}: Read {} of {} bytes", self.token, self.rec_buf.len(), self.rec_size);
 					if self.rec_size != 0 && self.rec_buf.len() == self.rec_size {
 						self.rec_size = 0;
@@ -108,10 +166,20 @@ impl<Socket: GenericSocket> GenericConnection<Socket> {
 		}
 	}
 
-	/// Add a packet to send queue.
+	/// Add a packet to send queue. If the queue would grow past the limit the packet is
+	/// dropped and the connection is flagged as overflowed, see `is_overflowed`.
 	pub fn send<Message>(&mut self, io: &IoContext<Message>, data: Bytes) where Message: Send + Clone + Sync + 'static {
+		if self.overflowed {
+			return;
+		}
+		if self.queued_bytes + data.len() > self.max_queued_bytes {
+			warn!(target:"network", "{}: Send queue is full ({} bytes queued), dropping peer", self.token, self.queued_bytes);
+			self.overflowed = true;
+			return;
+		}
 		if !data.is_empty() {
 			trace!(target:"network", "{}: Sending {} bytes", self.token, data.len());
+			self.queued_bytes += data.len();
 			self.send_queue.push_back(Cursor::new(data));
 			if !self.interest.is_writable() {
 				self.interest.insert(Ready::writable());
@@ -125,6 +193,49 @@ impl<Socket: GenericSocket> GenericConnection<Socket> {
 		self.interest.is_writable()
 	}
 
+	/// Check if a packet had to be dropped because the peer is not reading fast enough.
+	/// Such a connection should be closed.
+	pub fn is_overflowed(&self) -> bool {
+		self.overflowed
+	}
+
+	/// Number of queued bytes which haven't been written to the socket yet.
+	pub fn queued_bytes(&self) -> usize {
+		self.queued_bytes
+	}
+
+	/// Set the maximum total size of the packets waiting to be sent.
+	pub fn set_max_queued_bytes(&mut self, max: usize) {
+		self.max_queued_bytes = max;
+	}
+
+	/// Cap the number of bytes written to the socket per second. 0 means no limit.
+	pub fn set_max_write_rate(&mut self, bytes_per_second: u32) {
+		self.outbound_limiter = match bytes_per_second {
+			0 => None,
+			rate => Some(OutboundLimiter::new(rate)),
+		};
+	}
+
+	/// Resume writing if it was deferred because the outbound byte rate was exceeded.
+	/// Nothing else re-arms a throttled connection, so this should be called periodically.
+	pub fn resume_throttled<Message>(&mut self, io: &IoContext<Message>) where Message: Send + Clone + Sync + 'static {
+		if self.throttled {
+			self.throttled = false;
+			io.update_registration(self.token).ok();
+		}
+	}
+
+	/// Total number of bytes read from the socket so far.
+	pub fn bytes_read(&self) -> u64 {
+		self.bytes_read
+	}
+
+	/// Total number of bytes written to the socket so far.
+	pub fn bytes_written(&self) -> u64 {
+		self.bytes_written
+	}
+
 	/// Writable IO handler. Called when the socket is ready to send.
 	pub fn writable<Message>(&mut self, io: &IoContext<Message>) -> Result<WriteStatus, Error> where Message: Send + Clone + Sync + 'static {
 		{
@@ -139,12 +250,34 @@ impl<Socket: GenericSocket> GenericConnection<Socket> {
 				return Ok(WriteStatus::Complete)
 			}
 
-			match self.socket.try_write(Buf::bytes(&buf)) {
+			let allowed = match self.outbound_limiter {
+				Some(ref mut limiter) => limiter.allowance(Instant::now(), send_size - pos),
+				None => send_size - pos,
+			};
+			if allowed == 0 {
+				// leave the writable interest unarmed until `resume_throttled` is called
+				trace!(target:"network", "{}: Outbound byte rate exceeded, deferring write", self.token);
+				self.throttled = true;
+				return Ok(WriteStatus::Ongoing);
+			}
+
+			let data = Buf::bytes(&buf);
+			match self.socket.try_write(&data[..min(data.len(), allowed)]) {
 				Ok(Some(size)) if (pos + size) < send_size => {
 					buf.advance(size);
+					self.queued_bytes -= size;
+					self.bytes_written += size as u64;
+					if let Some(ref mut limiter) = self.outbound_limiter {
+						limiter.consume(size);
+					}
 					Ok(WriteStatus::Ongoing)
 				},
 				Ok(Some(size)) if (pos + size) == send_size => {
+					self.queued_bytes -= size;
+					self.bytes_written += size as u64;
+					if let Some(ref mut limiter) = self.outbound_limiter {
+						limiter.consume(size);
+					}
 					trace!(target:"network", "{}: Wrote {} bytes", self.token, send_size);
 					Ok(WriteStatus::Complete)
 				},
@@ -175,6 +308,13 @@ impl Connection {
 			token,
 			socket,
 			send_queue: VecDeque::new(),
+			queued_bytes: 0,
+			max_queued_bytes: MAX_QUEUED_BYTES,
+			overflowed: false,
+			bytes_read: 0,
+			bytes_written: 0,
+			outbound_limiter: None,
+			throttled: false,
 			rec_buf: Bytes::new(),
 			rec_size: 0,
 			interest: Ready::hup() | Ready::readable(),
@@ -213,6 +353,13 @@ impl Connection {
 			rec_buf: Vec::new(),
 			rec_size: 0,
 			send_queue: self.send_queue.clone(),
+			queued_bytes: self.queued_bytes,
+			max_queued_bytes: self.max_queued_bytes,
+			overflowed: self.overflowed,
+			bytes_read: self.bytes_read,
+			bytes_written: self.bytes_written,
+			outbound_limiter: self.outbound_limiter.clone(),
+			throttled: self.throttled,
 			interest: Ready::hup(),
 			registered: AtomicBool::new(false),
 		})
@@ -490,7 +637,7 @@ impl EncryptedConnection {
 mod tests {
 	use std::cmp;
 	use std::collections::VecDeque;
-	use std::io::{Cursor, Error, ErrorKind, Read, Result, Write};
+	use std::io::{Error, ErrorKind, Read, Result, Write};
 	use std::sync::atomic::AtomicBool;
 
 	use mio::Ready;
@@ -606,6 +753,13 @@ mod tests {
 				token: 999998888usize,
 				socket: TestSocket::new(),
 				send_queue: VecDeque::new(),
+				queued_bytes: 0,
+				max_queued_bytes: MAX_QUEUED_BYTES,
+				overflowed: false,
+				bytes_read: 0,
+				bytes_written: 0,
+				outbound_limiter: None,
+				throttled: false,
 				rec_buf: Bytes::new(),
 				rec_size: 0,
 				interest: Ready::hup() | Ready::readable(),
@@ -628,6 +782,13 @@ mod tests {
 				token: 999998888usize,
 				socket: TestBrokenSocket { error: "test broken socket".to_owned() },
 				send_queue: VecDeque::new(),
+				queued_bytes: 0,
+				max_queued_bytes: MAX_QUEUED_BYTES,
+				overflowed: false,
+				bytes_read: 0,
+				bytes_written: 0,
+				outbound_limiter: None,
+				throttled: false,
 				rec_buf: Bytes::new(),
 				rec_size: 0,
 				interest: Ready::hup() | Ready::readable(),
@@ -682,8 +843,7 @@ mod tests {
 	#[test]
 	fn connection_write() {
 		let mut connection = TestConnection::new();
-		let data = Cursor::new(vec![0; 10240]);
-		connection.send_queue.push_back(data);
+		connection.send(&test_io(), vec![0; 10240]);
 
 		let status = connection.writable(&test_io());
 		assert!(status.is_ok());
@@ -695,21 +855,75 @@ mod tests {
 	fn connection_write_is_buffered() {
 		let mut connection = TestConnection::new();
 		connection.socket = TestSocket::new_buf(1024);
-		let data = Cursor::new(vec![0; 10240]);
-		connection.send_queue.push_back(data);
+		connection.send(&test_io(), vec![0; 10240]);
 
 		let status = connection.writable(&test_io());
 
 		assert!(status.is_ok());
 		assert!(WriteStatus::Ongoing == status.unwrap());
 		assert_eq!(1024, connection.socket.write_buffer.len());
+		assert_eq!(10240 - 1024, connection.queued_bytes());
+	}
+
+	#[test]
+	fn connection_send_over_limit() {
+		let mut connection = TestConnection::new();
+		connection.set_max_queued_bytes(1000);
+
+		connection.send(&test_io(), vec![0; 600]);
+		assert!(!connection.is_overflowed());
+		connection.send(&test_io(), vec![0; 600]);
+		assert!(connection.is_overflowed());
+		assert_eq!(1, connection.send_queue.len());
+		assert_eq!(600, connection.queued_bytes());
+	}
+
+	#[test]
+	fn connection_write_releases_queue_space() {
+		let mut connection = TestConnection::new();
+		connection.set_max_queued_bytes(1000);
+		connection.socket = TestSocket::new_buf(256);
+
+		connection.send(&test_io(), vec![0; 600]);
+		while connection.writable(&test_io()).unwrap() == WriteStatus::Ongoing {}
+		assert_eq!(0, connection.queued_bytes());
+
+		connection.send(&test_io(), vec![0; 600]);
+		assert!(!connection.is_overflowed());
+		assert_eq!(1200, connection.socket.write_buffer.len() + connection.queued_bytes());
+	}
+
+	#[test]
+	fn connection_write_is_rate_limited() {
+		let mut connection = TestConnection::new();
+		connection.set_max_write_rate(1000);
+		connection.send(&test_io(), vec![0; 2500]);
+
+		// the first second worth of bytes goes out right away, then writes are deferred
+		assert!(WriteStatus::Ongoing == connection.writable(&test_io()).unwrap());
+		assert_eq!(1000, connection.socket.write_buffer.len());
+		assert!(WriteStatus::Ongoing == connection.writable(&test_io()).unwrap());
+		assert_eq!(1000, connection.socket.write_buffer.len());
+		assert!(connection.throttled);
+
+		// once the budget is refilled writing resumes
+		connection.resume_throttled(&test_io());
+		assert!(!connection.throttled);
+		connection.outbound_limiter.as_mut().unwrap().last_refill -= Duration::from_secs(1);
+		assert!(WriteStatus::Ongoing == connection.writable(&test_io()).unwrap());
+		assert_eq!(2000, connection.socket.write_buffer.len());
+
+		// the remainder is smaller than a second worth of bytes
+		connection.outbound_limiter.as_mut().unwrap().last_refill -= Duration::from_millis(500);
+		assert!(WriteStatus::Complete == connection.writable(&test_io()).unwrap());
+		assert_eq!(2500, connection.socket.write_buffer.len());
+		assert_eq!(2500, connection.bytes_written());
 	}
 
 	#[test]
 	fn connection_write_to_broken() {
 		let mut connection = TestBrokenConnection::new();
-		let data = Cursor::new(vec![0; 10240]);
-		connection.send_queue.push_back(data);
+		connection.send(&test_io(), vec![0; 10240]);
 
 		let status = connection.writable(&test_io());
 
@@ -730,6 +944,21 @@ mod tests {
 		assert_eq!(1024, connection.socket.cursor);
 	}
 
+	#[test]
+	fn connection_counts_traffic() {
+		let mut connection = TestConnection::new();
+		connection.socket = TestSocket::new_buf(256);
+		connection.socket.read_buffer = vec![99; 2048];
+
+		connection.send(&test_io(), vec![0; 600]);
+		while connection.writable(&test_io()).unwrap() == WriteStatus::Ongoing {}
+		assert_eq!(600, connection.bytes_written());
+
+		connection.expect(1500);
+		assert_eq!(1500, connection.readable().unwrap().unwrap().len());
+		assert_eq!(1500, connection.bytes_read());
+	}
+
 	#[test]
 	fn connection_read_from_broken() {
 		let mut connection = TestBrokenConnection::new();
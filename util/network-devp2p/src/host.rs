@@ -18,13 +18,13 @@ use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io::{self, Read, Write};
-use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::ops::*;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering as AtomicOrdering};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ethereum_types::H256;
 use keccak_hash::keccak;
@@ -43,7 +43,7 @@ use parity_crypto::publickey::{Generator, KeyPair, Random, Secret};
 use network::{
 	client_version::ClientVersion, ConnectionDirection, ConnectionFilter, DisconnectReason, Error,
 	NetworkConfiguration, NetworkContext as NetworkContextTrait, NetworkIoMessage, NetworkProtocolHandler,
-	NonReservedPeerMode, PacketId, PeerId, ProtocolId, SessionInfo
+	NonReservedPeerMode, PacketId, PeerId, ProtocolId, SessionInfo, SessionStats
 };
 
 use crate::{
@@ -159,6 +159,19 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 		Ok(())
 	}
 
+	fn broadcast(&self, packet_id: PacketId, data: &[u8]) {
+		let sessions: Vec<_> = self.sessions.read().iter().cloned().collect();
+		for session in sessions {
+			let mut s = session.lock();
+			if s.expired() || !s.is_ready() || !s.have_capability(self.protocol) {
+				continue;
+			}
+			if let Err(e) = s.send_packet(self.io, Some(self.protocol), packet_id as u8, data) {
+				trace!(target: "network", "Broadcast to {} failed: {:?}", s.token(), e);
+			}
+		}
+	}
+
 	fn respond(&self, packet_id: PacketId, data: Vec<u8>) -> Result<(), Error> {
 		assert!(self.session.is_some(), "Respond called without network context");
 		self.session_id.map_or_else(|| Err(Error::Expired), |id| self.send(id, packet_id, data))
@@ -174,6 +187,11 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
 	}
 
+	fn disconnect_peer_with_reason(&self, peer: PeerId, reason: DisconnectReason) {
+		self.io.message(NetworkIoMessage::DisconnectWithReason(peer, reason))
+			.unwrap_or_else(|e| warn!("Error sending network IO message: {:?}", e));
+	}
+
 	fn is_expired(&self) -> bool {
 		self.session.as_ref().map_or(false, |s| s.lock().expired())
 	}
@@ -195,6 +213,10 @@ impl<'s> NetworkContextTrait for NetworkContext<'s> {
 		self.resolve_session(peer).map(|s| s.lock().info.clone())
 	}
 
+	fn session_stats(&self, peer: PeerId) -> Option<SessionStats> {
+		self.resolve_session(peer).map(|s| s.lock().stats())
+	}
+
 	fn protocol_version(&self, protocol: ProtocolId, peer: PeerId) -> Option<u8> {
 		let session = self.resolve_session(peer);
 		session.and_then(|s| s.lock().capability_version(protocol))
@@ -242,6 +264,10 @@ impl HostInfo {
 		&self.config.client_version
 	}
 
+	pub(crate) fn max_outbound_bytes_per_second(&self) -> u32 {
+		self.config.max_outbound_bytes_per_second
+	}
+
 	pub(crate) fn secret(&self) -> &Secret {
 		self.keys.secret()
 	}
@@ -253,6 +279,68 @@ impl HostInfo {
 
 type SharedSession = Arc<Mutex<Session>>;
 
+/// Token bucket limiting the rate at which inbound connections are accepted.
+struct InboundLimiter {
+	/// Accepts allowed per second, also used as the burst size. 0 means no limit.
+	rate: u32,
+	tokens: f64,
+	last_refill: Instant,
+}
+
+impl InboundLimiter {
+	fn new(rate: u32) -> Self {
+		InboundLimiter {
+			rate,
+			tokens: rate as f64,
+			last_refill: Instant::now(),
+		}
+	}
+
+	/// Take a token for a connection accepted at `now`. Returns false if the rate is exceeded.
+	fn try_acquire(&mut self, now: Instant) -> bool {
+		if self.rate == 0 {
+			return true;
+		}
+		let elapsed = now.checked_duration_since(self.last_refill).unwrap_or_default();
+		self.last_refill = max(self.last_refill, now);
+		self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate as f64).min(self.rate as f64);
+		if self.tokens >= 1.0 {
+			self.tokens -= 1.0;
+			true
+		} else {
+			false
+		}
+	}
+}
+
+/// Live inbound connections, counted per remote IP address.
+#[derive(Default)]
+struct InboundConnections {
+	by_token: HashMap<StreamToken, IpAddr>,
+	by_ip: HashMap<IpAddr, usize>,
+}
+
+impl InboundConnections {
+	fn count(&self, ip: &IpAddr) -> usize {
+		self.by_ip.get(ip).cloned().unwrap_or(0)
+	}
+
+	fn insert(&mut self, token: StreamToken, ip: IpAddr) {
+		if self.by_token.insert(token, ip).is_none() {
+			*self.by_ip.entry(ip).or_insert(0) += 1;
+		}
+	}
+
+	fn remove(&mut self, token: StreamToken) {
+		if let Some(ip) = self.by_token.remove(&token) {
+			let remaining = self.by_ip.get_mut(&ip).map(|count| { *count -= 1; *count });
+			if remaining == Some(0) {
+				self.by_ip.remove(&ip);
+			}
+		}
+	}
+}
+
 #[derive(Copy, Clone)]
 struct ProtocolTimer {
 	pub protocol: ProtocolId,
@@ -275,6 +363,8 @@ pub struct Host {
 	reserved_nodes: RwLock<HashSet<NodeId>>,
 	stopping: AtomicBool,
 	filter: Option<Arc<dyn ConnectionFilter>>,
+	inbound_limiter: Mutex<InboundLimiter>,
+	inbound_connections: Mutex<InboundConnections>,
 }
 
 impl Host {
@@ -309,6 +399,7 @@ impl Host {
 		let boot_nodes = config.boot_nodes.clone();
 		let reserved_nodes = config.reserved_nodes.clone();
 		config.max_handshakes = min(config.max_handshakes, MAX_HANDSHAKES as u32);
+		let inbound_limiter = InboundLimiter::new(config.max_inbound_per_second);
 
 		let mut host = Host {
 			info: RwLock::new(HostInfo {
@@ -331,10 +422,14 @@ impl Host {
 			reserved_nodes: RwLock::new(HashSet::new()),
 			stopping: AtomicBool::new(false),
 			filter,
+			inbound_limiter: Mutex::new(inbound_limiter),
+			inbound_connections: Mutex::new(InboundConnections::default()),
 		};
 
 		for n in boot_nodes {
-			host.add_node(&n);
+			if let Err(e) = host.add_node(&n) {
+				debug!(target: "network", "Could not add node {}: {:?}", n, e);
+			}
 		}
 
 		for n in reserved_nodes {
@@ -345,18 +440,16 @@ impl Host {
 		Ok(host)
 	}
 
-	pub fn add_node(&mut self, id: &str) {
-		match Node::from_str(id) {
-			Err(e) => { debug!(target: "network", "Could not add node {}: {:?}", id, e); },
-			Ok(n) => {
-				let entry = NodeEntry { endpoint: n.endpoint.clone(), id: n.id };
+	pub fn add_node(&mut self, id: &str) -> Result<(), Error> {
+		let n = Node::from_str(id)?;
 
-				self.nodes.write().add_node(n);
-				if let Some(ref mut discovery) = *self.discovery.lock() {
-					discovery.add_node(entry);
-				}
-			}
+		let entry = NodeEntry { endpoint: n.endpoint.clone(), id: n.id };
+		self.nodes.write().add_node(n);
+		if let Some(ref mut discovery) = *self.discovery.lock() {
+			discovery.add_node(entry);
 		}
+
+		Ok(())
 	}
 
 	pub fn add_reserved_node(&self, id: &str) -> Result<(), Error> {
@@ -364,7 +457,10 @@ impl Host {
 
 		let entry = NodeEntry { endpoint: n.endpoint.clone(), id: n.id };
 		self.reserved_nodes.write().insert(n.id);
-		self.nodes.write().add_node(Node::new(entry.id, entry.endpoint.clone()));
+		self.nodes.write().add_node(Node {
+			peer_type: PeerType::Required,
+			..n
+		});
 
 		if let Some(ref mut discovery) = *self.discovery.lock() {
 			discovery.add_node(entry);
@@ -406,10 +502,41 @@ impl Host {
 	pub fn remove_reserved_node(&self, id: &str) -> Result<(), Error> {
 		let n = Node::from_str(id)?;
 		self.reserved_nodes.write().remove(&n.id);
+		self.nodes.write().set_peer_type(&n.id, PeerType::Optional);
 
 		Ok(())
 	}
 
+	/// Add a reserved peer and start connecting to it right away. Fails if the enode can't be
+	/// parsed or the connection can't be initiated; being connected to the peer already is fine.
+	pub fn add_reserved_peer(&self, enode: &str, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
+		self.add_reserved_node(enode)?;
+		let id = Node::from_str(enode)?.id;
+		match self.connect_peer(&id, io) {
+			Ok(()) | Err(Error::DuplicatePeer) => Ok(()),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Remove a reserved peer. The peer stays connected unless only reserved peers are allowed.
+	pub fn remove_reserved_peer(&self, enode: &str, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
+		self.remove_reserved_node(enode)?;
+		if self.info.read().config.non_reserved_mode == NonReservedPeerMode::Deny {
+			let id = Node::from_str(enode)?.id;
+			let token = self.sessions.read().iter()
+				.map(|e| e.lock())
+				.find(|s| s.id() == Some(&id))
+				.map(|mut s| {
+					s.disconnect(io, DisconnectReason::ClientQuit);
+					s.token()
+				});
+			if let Some(token) = token {
+				self.kill_connection(token, io, false);
+			}
+		}
+		Ok(())
+	}
+
 	pub fn external_url(&self) -> Option<String> {
 		let info = self.info.read();
 		info.public_endpoint.as_ref().map(|e| format!("{}", Node::new(*info.id(), e.clone())))
@@ -432,6 +559,11 @@ impl Host {
 			trace!(target: "network", "Disconnecting on shutdown: {}", p);
 			self.kill_connection(p, io, true);
 		}
+		io.deregister_stream(TCP_ACCEPT).unwrap_or_else(|e| debug!("Error deregistering listener: {:?}", e));
+		if self.discovery.lock().is_some() {
+			io.deregister_stream(DISCOVERY).unwrap_or_else(|e| debug!("Error deregistering discovery socket: {:?}", e));
+		}
+		self.nodes.read().save();
 		io.unregister_handler();
 	}
 
@@ -539,13 +671,16 @@ impl Host {
 		let mut to_kill = Vec::new();
 		for e in self.sessions.read().iter() {
 			let mut s = e.lock();
-			if !s.keep_alive(io) {
+			if s.is_send_queue_overflowed() {
+				s.disconnect(io, DisconnectReason::TCPError);
+				to_kill.push(s.token());
+			} else if !s.keep_alive(io) {
 				s.disconnect(io, DisconnectReason::PingTimeout);
 				to_kill.push(s.token());
 			}
 		}
 		for p in to_kill {
-			trace!(target: "network", "Ping timeout: {}", p);
+			trace!(target: "network", "Killing unresponsive session: {}", p);
 			self.kill_connection(p, io, true);
 		}
 	}
@@ -576,50 +711,56 @@ impl Host {
 		let (handshake_count, egress_count, ingress_count) = self.session_count();
 		let reserved_nodes = self.reserved_nodes.read();
 		if egress_count + ingress_count >= min_peers as usize + reserved_nodes.len() {
-			// check if all pinned nodes are connected.
-			if reserved_nodes.iter().all(|n| self.have_session(n) && self.connecting_to(n)) {
-				return;
-			}
-
-			// if not, only attempt connect to reserved peers
+			// only attempt connect to reserved peers
 			pin = true;
 		}
 
-		// allow 16 slots for incoming connections
-		if handshake_count >= max_handshakes {
-			return;
+		let connectable = |id: &NodeId|
+			!self.have_session(id) &&
+			!self.connecting_to(id) &&
+			*id != self_id &&
+			self.filter.as_ref().map_or(true, |f| f.connection_allowed(&self_id, id, ConnectionDirection::Outbound));
+
+		// reserved nodes are required peers, they are exempt from the handshake slot limits.
+		let mut started: usize = 0;
+		for id in reserved_nodes.iter().filter(|id| connectable(*id)) {
+			if let Err(e) = self.connect_peer(id, io) {
+				debug!(target: "network", "{}: Can't connect: {}", id, e);
+			}
+			started += 1;
 		}
 
-		// iterate over all nodes, reserved ones coming first.
 		// if we are pinned to only reserved nodes, ignore all others.
-		let nodes = reserved_nodes.iter().cloned().chain(if !pin {
-			self.nodes.read().nodes(&allow_ips)
-		} else {
-			Vec::new()
-		});
+		// allow 16 slots for incoming connections
+		if pin || handshake_count + started >= max_handshakes {
+			debug!(target: "network", "Connecting peers: {} sessions, {} pending + {} started", egress_count + ingress_count, handshake_count, started);
+			return;
+		}
 
 		let max_handshakes_per_round = max_handshakes / 2;
-		let mut started: usize = 0;
-		for id in nodes.filter(|id|
-				!self.have_session(id) &&
-				!self.connecting_to(id) &&
-				*id != self_id &&
-				self.filter.as_ref().map_or(true, |f| f.connection_allowed(&self_id, &id, ConnectionDirection::Outbound))
-			).take(min(max_handshakes_per_round, max_handshakes - handshake_count)) {
-			self.connect_peer(&id, io);
+		let optional = self.nodes.read().nodes(&allow_ips);
+		for id in optional.iter().filter(|id| !reserved_nodes.contains(*id) && connectable(*id))
+			.take(min(max_handshakes_per_round, max_handshakes - handshake_count - started)) {
+			if let Err(e) = self.connect_peer(id, io) {
+				debug!(target: "network", "{}: Can't connect: {}", id, e);
+			}
 			started += 1;
 		}
 		debug!(target: "network", "Connecting peers: {} sessions, {} pending + {} started", egress_count + ingress_count, handshake_count, started);
 	}
 
-	fn connect_peer(&self, id: &NodeId, io: &IoContext<NetworkIoMessage>) {
+	fn connect_peer(&self, id: &NodeId, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
+		if id == self.info.read().id() {
+			trace!(target: "network", "Aborted connect. Node is self.");
+			return Err(Error::SelfConnect);
+		}
 		if self.have_session(id) {
 			trace!(target: "network", "Aborted connect. Node already connected.");
-			return;
+			return Err(Error::DuplicatePeer);
 		}
 		if self.connecting_to(id) {
 			trace!(target: "network", "Aborted connect. Node already connecting.");
-			return;
+			return Err(Error::DuplicatePeer);
 		}
 
 		let socket = {
@@ -629,7 +770,7 @@ impl Host {
 					node.endpoint.address
 				} else {
 					debug!(target: "network", "Connection to expired node aborted");
-					return;
+					return Err(Error::PeerNotFound);
 				}
 			};
 			match TcpStream::connect(&address) {
@@ -640,36 +781,37 @@ impl Host {
 				Err(e) => {
 					debug!(target: "network", "{}: Can't connect to address {:?}: {:?}", id, address, e);
 					self.nodes.write().note_failure(&id);
-					return;
+					return Err(Error::ConnectFailed(e));
 				}
 			}
 		};
 
-		if let Err(e) = self.create_connection(socket, Some(id), io) {
-			debug!(target: "network", "Can't create connection: {:?}", e);
-		}
+		self.create_connection(socket, Some(id), io).map(|_| ())
 	}
 
-	fn create_connection(&self, socket: TcpStream, id: Option<&NodeId>, io: &IoContext<NetworkIoMessage>) -> Result<(), Error> {
+	fn create_connection(&self, socket: TcpStream, id: Option<&NodeId>, io: &IoContext<NetworkIoMessage>) -> Result<StreamToken, Error> {
 		let nonce = self.info.write().next_nonce();
 		let mut sessions = self.sessions.write();
 
+		let mut create_error = None;
 		let token = sessions.insert_with_opt(|token| {
 			trace!(target: "network", "{}: Initiating session {:?}", token, id);
 			match Session::new(io, socket, token, id, &nonce, &self.info.read()) {
 				Ok(s) => Some(Arc::new(Mutex::new(s))),
 				Err(e) => {
 					debug!(target: "network", "Session create error: {:?}", e);
+					create_error = Some(e);
 					None
 				}
 			}
 		});
 
-		match token {
-			Some(t) => io.register_stream(t).map(|_| ()).map_err(Into::into),
-			None => {
+		match (token, create_error) {
+			(Some(t), _) => io.register_stream(t).map(|_| t).map_err(Into::into),
+			(None, Some(e)) => Err(Error::HandshakeFailed(Box::new(e))),
+			(None, None) => {
 				debug!(target: "network", "Max sessions reached");
-				Ok(())
+				Err(Error::TooManyPeers)
 			}
 		}
 	}
@@ -677,8 +819,8 @@ impl Host {
 	fn accept(&self, io: &IoContext<NetworkIoMessage>) {
 		trace!(target: "network", "Accepting incoming connection");
 		loop {
-			let socket = match self.tcp_listener.lock().accept() {
-				Ok((sock, _addr)) => sock,
+			let (socket, addr) = match self.tcp_listener.lock().accept() {
+				Ok(accepted) => accepted,
 				Err(e) => {
 					if e.kind() != io::ErrorKind::WouldBlock {
 						debug!(target: "network", "Error accepting connection: {:?}", e);
@@ -686,8 +828,19 @@ impl Host {
 					break
 				},
 			};
-			if let Err(e) = self.create_connection(socket, None, io) {
-				debug!(target: "network", "Can't accept connection: {:?}", e);
+			// dropping the socket closes the connection before a session slot is allocated.
+			if !self.inbound_limiter.lock().try_acquire(Instant::now()) {
+				debug!(target: "network", "Inbound connection rate exceeded, refusing {}", addr);
+				continue;
+			}
+			let max_per_ip = self.info.read().config.max_connections_per_ip as usize;
+			if max_per_ip != 0 && self.inbound_connections.lock().count(&addr.ip()) >= max_per_ip {
+				debug!(target: "network", "Too many connections from {}, refusing", addr.ip());
+				continue;
+			}
+			match self.create_connection(socket, None, io) {
+				Ok(token) => self.inbound_connections.lock().insert(token, addr.ip()),
+				Err(e) => debug!(target: "network", "Can't accept connection: {:?}", e),
 			}
 		}
 	}
@@ -761,6 +914,13 @@ impl Host {
 
 							let id = *s.id().expect("Ready session always has id");
 
+							if id == self_id {
+								trace!(target: "network", "Disconnecting self-connection on session {}", token);
+								s.disconnect(io, DisconnectReason::LocalIdentity);
+								kill = true;
+								break;
+							}
+
 							// Check for the session limit.
 							// Outgoing connections are allowed as long as their count is <= min_peers
 							// Incoming connections are allowed to take all of the max_peers reserve, or at most half of the slots.
@@ -928,6 +1088,15 @@ impl Host {
 		self.kill_connection(token, io, true)
 	}
 
+	fn disconnect_requested(&self, peer: PeerId, reason: DisconnectReason, io: &IoContext<NetworkIoMessage>) {
+		let session = { self.sessions.read().get(peer).cloned() };
+		if let Some(session) = session {
+			session.lock().disconnect(io, reason);
+		}
+		trace!(target: "network", "Disconnect requested {}: {:?}", peer, reason);
+		self.kill_connection(peer, io, false);
+	}
+
 	fn kill_connection(&self, token: StreamToken, io: &IoContext<NetworkIoMessage>, remote: bool) {
 		let mut to_disconnect: Vec<ProtocolId> = Vec::new();
 		let mut failure_id = None;
@@ -948,6 +1117,7 @@ impl Host {
 					}
 					s.set_expired();
 					failure_id = s.id().cloned();
+					self.inbound_connections.lock().remove(token);
 				}
 				deregister = remote || s.done();
 			}
@@ -1071,7 +1241,21 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NODE_TABLE => {
 				trace!(target: "network", "Refreshing node table");
+				// resolve outside of the lock, name lookups may block for a while
+				let host_names = self.nodes.read().host_names();
+				let resolved: Vec<_> = host_names.into_iter()
+					.filter_map(|(id, host_name)| match NodeEndpoint::from_str(&host_name) {
+						Ok(endpoint) => Some((id, endpoint)),
+						Err(e) => {
+							debug!(target: "network", "Error resolving {}: {:?}", host_name, e);
+							None
+						},
+					})
+					.collect();
 				let mut nodes = self.nodes.write();
+				for (id, endpoint) in resolved {
+					nodes.set_endpoint(&id, endpoint);
+				}
 				nodes.clear_useless();
 				nodes.save();
 			},
@@ -1129,12 +1313,10 @@ impl IoHandler<NetworkIoMessage> for Host {
 				io.register_timer(handler_token, *delay).unwrap_or_else(|e| debug!("Error registering timer {}: {:?}", token, e));
 			},
 			NetworkIoMessage::Disconnect(ref peer) => {
-				let session = { self.sessions.read().get(*peer).cloned() };
-				if let Some(session) = session {
-					session.lock().disconnect(io, DisconnectReason::DisconnectRequested);
-				}
-				trace!(target: "network", "Disconnect requested {}", peer);
-				self.kill_connection(*peer, io, false);
+				self.disconnect_requested(*peer, DisconnectReason::DisconnectRequested, io);
+			},
+			NetworkIoMessage::DisconnectWithReason(ref peer, reason) => {
+				self.disconnect_requested(*peer, reason, io);
 			},
 			NetworkIoMessage::DisablePeer(ref peer) => {
 				let session = { self.sessions.read().get(*peer).cloned() };
@@ -1151,6 +1333,7 @@ impl IoHandler<NetworkIoMessage> for Host {
 			},
 			NetworkIoMessage::InitPublicInterface =>
 				self.init_public_interface(io).unwrap_or_else(|e| warn!("Error initializing public interface: {:?}", e)),
+			NetworkIoMessage::Shutdown => self.stop(io),
 			_ => {}	// ignore others.
 		}
 	}
@@ -1187,7 +1370,10 @@ impl IoHandler<NetworkIoMessage> for Host {
 					}
 				}
 			}
-			DISCOVERY => (),
+			DISCOVERY => if let Some(udp_socket) = self.udp_socket.lock().as_ref() {
+				event_loop.deregister(udp_socket).unwrap_or_else(|e| debug!("Error deregistering UDP socket: {:?}", e));
+			},
+			TCP_ACCEPT => event_loop.deregister(&*self.tcp_listener.lock()).unwrap_or_else(|e| debug!("Error deregistering stream: {:?}", e)),
 			_ => warn!("Unexpected stream deregistration")
 		}
 	}
@@ -1287,3 +1473,132 @@ fn host_client_url() {
 	let host: Host = Host::new(config, None).unwrap();
 	assert!(host.local_url().starts_with("enode://101b3ef5a4ea7a1c7928e24c4c75fd053c235d7b80c22ae5c03d145d0ac7396e2a4ffff9adee3133a7b05044a5cee08115fd65145e5165d646bde371010d803c@"));
 }
+
+#[test]
+fn host_reserved_nodes() {
+	let host: Host = Host::new(NetworkConfiguration::new_local(), None).unwrap();
+	let enode = "enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770";
+	let id = NodeId::from_str("a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c").unwrap();
+
+	assert!(host.add_reserved_node("enode://not-a-node@1.2.3.4:30303").is_err());
+	assert!(host.reserved_nodes.read().is_empty());
+
+	host.add_reserved_node(enode).unwrap();
+	assert!(host.reserved_nodes.read().contains(&id));
+	assert_eq!(host.nodes.read().get(&id).unwrap().peer_type, PeerType::Required);
+
+	host.remove_reserved_node(enode).unwrap();
+	assert!(!host.reserved_nodes.read().contains(&id));
+	assert_eq!(host.nodes.read().get(&id).unwrap().peer_type, PeerType::Optional);
+}
+
+#[test]
+fn host_add_reserved_peer_connects() {
+	use std::net::TcpListener;
+	use ethcore_io::IoChannel;
+
+	let host: Host = Host::new(NetworkConfiguration::new_local(), None).unwrap();
+	let io = IoContext::new(IoChannel::disconnected(), 0);
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let id = *Random.generate().unwrap().public();
+	let enode = format!("enode://{:x}@{}", id, listener.local_addr().unwrap());
+
+	assert!(host.add_reserved_peer("enode://not-a-node@1.2.3.4:30303", &io).is_err());
+	assert!(host.reserved_nodes.read().is_empty());
+	assert!(!host.connecting_to(&id));
+
+	host.add_reserved_peer(&enode, &io).unwrap();
+	assert_eq!(host.nodes.read().get(&id).unwrap().peer_type, PeerType::Required);
+	assert!(host.connecting_to(&id));
+
+	// adding it again while connecting is fine
+	host.add_reserved_peer(&enode, &io).unwrap();
+
+	host.remove_reserved_peer(&enode, &io).unwrap();
+	assert!(!host.reserved_nodes.read().contains(&id));
+	assert_eq!(host.nodes.read().get(&id).unwrap().peer_type, PeerType::Optional);
+}
+
+#[test]
+fn inbound_limiter_refuses_burst_over_rate() {
+	let mut limiter = InboundLimiter::new(4);
+	let now = Instant::now();
+	let accepted = (0..10).filter(|_| limiter.try_acquire(now)).count();
+	assert_eq!(accepted, 4);
+
+	// half a second later two more tokens are available.
+	let later = now + Duration::from_millis(500);
+	let accepted = (0..10).filter(|_| limiter.try_acquire(later)).count();
+	assert_eq!(accepted, 2);
+}
+
+#[test]
+fn inbound_connections_counted_per_ip() {
+	let mut connections = InboundConnections::default();
+	let a: IpAddr = "10.0.0.1".parse().unwrap();
+	let b: IpAddr = "10.0.0.2".parse().unwrap();
+	connections.insert(FIRST_SESSION, a);
+	connections.insert(FIRST_SESSION + 1, a);
+	connections.insert(FIRST_SESSION + 2, b);
+	assert_eq!(connections.count(&a), 2);
+	assert_eq!(connections.count(&b), 1);
+
+	connections.remove(FIRST_SESSION);
+	connections.remove(FIRST_SESSION);
+	assert_eq!(connections.count(&a), 1);
+	connections.remove(FIRST_SESSION + 1);
+	assert_eq!(connections.count(&a), 0);
+	assert!(!connections.by_ip.contains_key(&a));
+}
+
+#[test]
+fn inbound_limiter_disabled_with_zero_rate() {
+	let mut limiter = InboundLimiter::new(0);
+	let now = Instant::now();
+	assert!((0..1000).all(|_| limiter.try_acquire(now)));
+}
+
+#[test]
+fn host_connect_failures() {
+	use ethcore_io::IoChannel;
+
+	let mut host: Host = Host::new(NetworkConfiguration::new_local(), None).unwrap();
+	let io = IoContext::new(IoChannel::disconnected(), 0);
+	let address = host.info.read().local_endpoint.address;
+	let endpoint = NodeEndpoint { address, udp_port: address.port() };
+
+	match host.add_node(&format!("enode://{}@127.0.0.1:30303", "zz".repeat(64))) {
+		Err(Error::InvalidNodeId) => (),
+		other => panic!("Unexpected result: {:?}", other),
+	}
+
+	match host.connect_peer(&NodeId::from_low_u64_be(1), &io) {
+		Err(Error::PeerNotFound) => (),
+		other => panic!("Unexpected result: {:?}", other),
+	}
+
+	// not a valid public key, so the auth message can't be encrypted to it.
+	let invalid_key = NodeId::from_low_u64_be(2);
+	host.nodes.write().add_node(Node::new(invalid_key, endpoint.clone()));
+	match host.connect_peer(&invalid_key, &io) {
+		Err(Error::HandshakeFailed(_)) => (),
+		other => panic!("Unexpected result: {:?}", other),
+	}
+
+	// refused before a session slot is taken.
+	let self_id = *host.info.read().id();
+	host.nodes.write().add_node(Node::new(self_id, endpoint.clone()));
+	match host.connect_peer(&self_id, &io) {
+		Err(Error::SelfConnect) => (),
+		other => panic!("Unexpected result: {:?}", other),
+	}
+	assert_eq!(host.sessions.read().count(), 0);
+
+	let peer = *Random.generate().unwrap().public();
+	host.nodes.write().add_node(Node::new(peer, endpoint));
+	host.connect_peer(&peer, &io).unwrap();
+	match host.connect_peer(&peer, &io) {
+		Err(Error::DuplicatePeer) => (),
+		other => panic!("Unexpected result: {:?}", other),
+	}
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Ethereum.  If not, see <http://www.gnu.org/licenses/>.
 
-use std::{fs, slice};
+use std::{fs, io, slice};
 use std::collections::{HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
 use std::hash::{Hash, Hasher};
@@ -132,21 +132,31 @@ impl FromStr for NodeEndpoint {
 
 	/// Create endpoint from string. Performs name resolution if given a host name.
 	fn from_str(s: &str) -> Result<NodeEndpoint, Error> {
-		let address = s.to_socket_addrs().map(|mut i| i.next());
+		NodeEndpoint::from_str_with_resolver(s, |s| s.to_socket_addrs().map(Iterator::collect))
+	}
+}
+
+impl NodeEndpoint {
+	/// Create endpoint from string, resolving host names with the given resolver.
+	/// IPv4 addresses are preferred when a host name resolves to several addresses.
+	pub fn from_str_with_resolver<F>(s: &str, resolve: F) -> Result<NodeEndpoint, Error>
+		where F: FnOnce(&str) -> io::Result<Vec<SocketAddr>>
+	{
+		let addresses = resolve(s).map_err(|_| Error::AddressParse)?; // always an io::Error of InvalidInput kind
+		let address = addresses.iter().find(|a| a.is_ipv4()).or_else(|| addresses.first());
 		match address {
-			Ok(Some(a)) => Ok(NodeEndpoint {
+			Some(&a) => Ok(NodeEndpoint {
 				address: a,
 				udp_port: a.port()
 			}),
-			Ok(None) => return Err(Error::AddressResolve(None.into())),
-			Err(_) => Err(Error::AddressParse) // always an io::Error of InvalidInput kind
+			None => Err(Error::AddressResolve(None.into())),
 		}
 	}
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum PeerType {
-	_Required,
+	Required,
 	Optional
 }
 
@@ -193,6 +203,9 @@ pub struct Node {
 	pub endpoint: NodeEndpoint,
 	pub peer_type: PeerType,
 	pub last_contact: Option<NodeContact>,
+	/// Host name and port the endpoint was resolved from, kept so that the
+	/// address can be resolved again later.
+	pub host_name: Option<String>,
 }
 
 impl Node {
@@ -202,8 +215,34 @@ impl Node {
 			endpoint,
 			peer_type: PeerType::Optional,
 			last_contact: None,
+			host_name: None,
 		}
 	}
+
+	/// Create node from an enode url or a bare endpoint, resolving host names with the given resolver.
+	pub fn from_str_with_resolver<F>(s: &str, resolve: F) -> Result<Self, Error>
+		where F: FnOnce(&str) -> io::Result<Vec<SocketAddr>>
+	{
+		let (id, address) = if s.len() > 136 && &s[0..8] == "enode://" && &s[136..137] == "@" {
+			(s[8..136].parse().map_err(|_| Error::InvalidNodeId)?, &s[137..])
+		}
+		else {
+			(NodeId::default(), s)
+		};
+		let endpoint = NodeEndpoint::from_str_with_resolver(address, resolve)?;
+		let host_name = match SocketAddr::from_str(address) {
+			Ok(_) => None,
+			Err(_) => Some(address.to_owned()),
+		};
+
+		Ok(Node {
+			id,
+			endpoint,
+			peer_type: PeerType::Optional,
+			last_contact: None,
+			host_name,
+		})
+	}
 }
 
 impl Display for Node {
@@ -220,19 +259,7 @@ impl Display for Node {
 impl FromStr for Node {
 	type Err = Error;
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let (id, endpoint) = if s.len() > 136 && &s[0..8] == "enode://" && &s[136..137] == "@" {
-			(s[8..136].parse().map_err(|_| Error::InvalidNodeId)?, NodeEndpoint::from_str(&s[137..])?)
-		}
-		else {
-			(NodeId::default(), NodeEndpoint::from_str(s)?)
-		};
-
-		Ok(Node {
-			id,
-			endpoint,
-			peer_type: PeerType::Optional,
-			last_contact: None,
-		})
+		Node::from_str_with_resolver(s, |s| s.to_socket_addrs().map(Iterator::collect))
 	}
 }
 
@@ -409,6 +436,13 @@ impl NodeTable {
 		self.nodes.contains_key(id)
 	}
 
+	/// Change the peer type of a node in the table.
+	pub fn set_peer_type(&mut self, id: &NodeId, peer_type: PeerType) {
+		if let Some(node) = self.nodes.get_mut(id) {
+			node.peer_type = peer_type;
+		}
+	}
+
 	/// Apply table changes coming from discovery
 	pub fn update(&mut self, mut update: TableUpdates, reserved: &HashSet<NodeId>) {
 		for (_, node) in update.added.drain() {
@@ -447,6 +481,20 @@ impl NodeTable {
 		}
 	}
 
+	/// Returns the nodes which were added by host name, along with that host name.
+	pub fn host_names(&self) -> Vec<(NodeId, String)> {
+		self.nodes.values()
+			.filter_map(|n| n.host_name.clone().map(|host_name| (n.id, host_name)))
+			.collect()
+	}
+
+	/// Replace the endpoint of a node, e.g. after resolving its host name again.
+	pub fn set_endpoint(&mut self, id: &NodeId, endpoint: NodeEndpoint) {
+		if let Some(node) = self.nodes.get_mut(id) {
+			node.endpoint = endpoint;
+		}
+	}
+
 	/// Set last contact as failure for a node
 	pub fn note_failure(&mut self, id: &NodeId) {
 		self.update_ordered_ids(id, Some(NodeContact::failure()));
@@ -599,8 +647,14 @@ mod json {
 				}
 			});
 
+			// keep the host name so that it is resolved again on load
+			let url = match node.host_name {
+				Some(ref host_name) => format!("enode://{:x}@{}", node.id, host_name),
+				None => format!("{}", node),
+			};
+
 			Node {
-				url: format!("{}", node),
+				url,
 				last_contact
 			}
 		}
@@ -633,6 +687,21 @@ mod tests {
 		assert_eq!(SocketAddrV4::new(Ipv4Addr::new(123, 99, 55, 44), 7770), v4);
 	}
 
+	#[test]
+	fn endpoint_parse_resolves_host_name() {
+		let v6 = SocketAddr::from_str("[2001:db8::1]:30300").unwrap();
+		let v4 = SocketAddr::from_str("10.0.0.1:30300").unwrap();
+		let endpoint = NodeEndpoint::from_str_with_resolver("gav.ethdev.com:30300", |host| {
+			assert_eq!(host, "gav.ethdev.com:30300");
+			Ok(vec![v6, v4])
+		}).unwrap();
+		assert_eq!(endpoint.address, v4);
+		assert_eq!(endpoint.udp_port, 30300);
+
+		let err = NodeEndpoint::from_str_with_resolver("gav.ethdev.com:30300", |_| Ok(vec![])).unwrap_err();
+		assert_matches!(err, Error::AddressResolve(_));
+	}
+
 	#[test]
 	fn endpoint_parse_empty_ip_string_returns_error() {
 		let endpoint = NodeEndpoint::from_str("");
@@ -672,6 +741,33 @@ mod tests {
 			node.id);
 	}
 
+	#[test]
+	fn node_parse_keeps_host_name() {
+		let address = SocketAddr::from_str("10.0.0.1:30300").unwrap();
+		let resolve = |host: &str| {
+			assert_eq!(host, "gav.ethdev.com:30300");
+			Ok(vec![address])
+		};
+		let node = Node::from_str_with_resolver("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@gav.ethdev.com:30300", resolve).unwrap();
+		assert_eq!(node.endpoint.address, address);
+		assert_eq!(node.host_name, Some("gav.ethdev.com:30300".to_owned()));
+		assert_eq!(
+			json::Node::from(&node).url,
+			"enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@gav.ethdev.com:30300");
+
+		let id = node.id;
+		let mut table = NodeTable::new(None);
+		table.add_node(node);
+		assert_eq!(table.host_names(), vec![(id, "gav.ethdev.com:30300".to_owned())]);
+
+		let moved = SocketAddr::from_str("10.0.0.2:30300").unwrap();
+		table.set_endpoint(&id, NodeEndpoint { address: moved, udp_port: 30300 });
+		assert_eq!(table.get(&id).unwrap().endpoint.address, moved);
+
+		let node = Node::from_str("enode://a979fb575495b8d6db44f750317d0f4622bf4c2aa3365d6af7c284339968eef29b69ad0dce72a4d8db5ebb4968de0e3bec910127f134779fbcb0cb6d3331163c@22.99.55.44:7770").unwrap();
+		assert_eq!(node.host_name, None);
+	}
+
 	#[test]
 	fn node_parse_fails_for_invalid_urls() {
 		let node = Node::from_str("foo");
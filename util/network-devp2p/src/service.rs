@@ -158,7 +158,8 @@ impl NetworkService {
 	pub fn add_reserved_peer(&self, peer: &str) -> Result<(), Error> {
 		let host = self.host.read();
 		if let Some(ref host) = *host {
-			host.add_reserved_node(peer)
+			let io = IoContext::new(self.io_service.channel(), 0);
+			host.add_reserved_peer(peer, &io)
 		} else {
 			Ok(())
 		}
@@ -168,7 +169,8 @@ impl NetworkService {
 	pub fn remove_reserved_peer(&self, peer: &str) -> Result<(), Error> {
 		let host = self.host.read();
 		if let Some(ref host) = *host {
-			host.remove_reserved_node(peer)
+			let io = IoContext::new(self.io_service.channel(), 0);
+			host.remove_reserved_peer(peer, &io)
 		} else {
 			Ok(())
 		}
@@ -28,14 +28,14 @@ use parity_snappy as snappy;
 use rlp::{EMPTY_LIST_RLP, Rlp, RlpStream};
 
 use ethcore_io::{IoContext, StreamToken};
-use network::{DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, SessionInfo};
+use network::{DisconnectReason, Error, PeerCapabilityInfo, ProtocolId, SessionInfo, SessionStats};
 use network::client_version::ClientVersion;
 use network::SessionCapabilityInfo;
 
 use crate::{
 	connection::{Connection, EncryptedConnection, MAX_PAYLOAD_SIZE, Packet},
 	handshake::Handshake,
-	host::HostInfo,
+	host::{CapabilityInfo, HostInfo},
 	node_table::NodeId,
 };
 
@@ -111,6 +111,7 @@ impl Session {
 		where Message: Send + Clone + Sync + 'static {
 		let originated = id.is_some();
 		let mut handshake = Handshake::new(token, id, socket, nonce).expect("Can't create handshake");
+		handshake.connection.set_max_write_rate(host.max_outbound_bytes_per_second());
 		let local_addr = handshake.connection.local_addr_str();
 		handshake.start(io, host, originated)?;
 		Ok(Session {
@@ -155,6 +156,15 @@ impl Session {
 		}
 	}
 
+	/// Traffic counters for this session.
+	pub fn stats(&self) -> SessionStats {
+		let connection = self.connection();
+		SessionStats {
+			bytes_received: connection.bytes_read(),
+			bytes_sent: connection.bytes_written(),
+		}
+	}
+
 	/// Get id of the remote peer
 	pub fn id(&self) -> Option<&NodeId> {
 		self.info.id.as_ref()
@@ -295,8 +305,17 @@ impl Session {
 		self.send(io, &rlp.drain())
 	}
 
+	/// Check if the peer stopped reading and packets had to be dropped.
+	pub fn is_send_queue_overflowed(&self) -> bool {
+		self.connection().is_overflowed()
+	}
+
 	/// Keep this session alive. Returns false if ping timeout happened
 	pub fn keep_alive<Message>(&mut self, io: &IoContext<Message>) -> bool where Message: Send + Sync + Clone {
+		match self.state {
+			State::Handshake(ref mut h) => h.connection.resume_throttled(io),
+			State::Session(ref mut s) => s.connection.resume_throttled(io),
+		}
 		if let State::Handshake(_) = self.state {
 			return true;
 		}
@@ -430,40 +449,7 @@ impl Session {
 		let peer_caps: Vec<PeerCapabilityInfo> = rlp.list_at(2)?;
 		let id = rlp.val_at::<NodeId>(4)?;
 
-		// Intersect with host capabilities
-		// Leave only highset mutually supported capability version
-		let mut caps: Vec<SessionCapabilityInfo> = Vec::new();
-		for hc in &host.capabilities {
-			if peer_caps.iter().any(|c| c.protocol == hc.protocol && c.version == hc.version) {
-				caps.push(SessionCapabilityInfo {
-					protocol: hc.protocol,
-					version: hc.version,
-					id_offset: 0,
-					packet_count: hc.packet_count,
-				});
-			}
-		}
-
-		caps.retain(|c| host.capabilities.iter().any(|hc| hc.protocol == c.protocol && hc.version == c.version));
-		let mut i = 0;
-		while i < caps.len() {
-			if caps.iter().any(|c| c.protocol == caps[i].protocol && c.version > caps[i].version) {
-				caps.remove(i);
-			} else {
-				i += 1;
-			}
-		}
-
-		// Sort capabilities alphabetically.
-		caps.sort();
-
-		i = 0;
-		let mut offset: u8 = PACKET_USER;
-		while i < caps.len() {
-			caps[i].id_offset = offset;
-			offset += caps[i].packet_count;
-			i += 1;
-		}
+		let caps = negotiate_capabilities(&host.capabilities, &peer_caps);
 		debug!(target: "network", "Hello: {} v{} {} {:?}", client_version, protocol, id, caps);
 		let protocol = ::std::cmp::min(protocol, host.protocol_version);
 		self.info.protocol_version = protocol;
@@ -519,3 +505,73 @@ impl Session {
 		Ok(())
 	}
 }
+
+/// Intersects our capabilities with the ones advertised by a peer, keeping only the
+/// highest mutually supported version of each protocol. The result is sorted
+/// alphabetically and has packet id offsets assigned.
+fn negotiate_capabilities(ours: &[CapabilityInfo], theirs: &[PeerCapabilityInfo]) -> Vec<SessionCapabilityInfo> {
+	let mut caps: Vec<SessionCapabilityInfo> = Vec::new();
+	for hc in ours {
+		if theirs.iter().any(|c| c.protocol == hc.protocol && c.version == hc.version) {
+			caps.push(SessionCapabilityInfo {
+				protocol: hc.protocol,
+				version: hc.version,
+				id_offset: 0,
+				packet_count: hc.packet_count,
+			});
+		}
+	}
+
+	// Leave only highest mutually supported capability version
+	let mut i = 0;
+	while i < caps.len() {
+		if caps.iter().any(|c| c.protocol == caps[i].protocol && c.version > caps[i].version) {
+			caps.remove(i);
+		} else {
+			i += 1;
+		}
+	}
+
+	// Sort capabilities alphabetically.
+	caps.sort();
+
+	let mut offset: u8 = PACKET_USER;
+	for cap in caps.iter_mut() {
+		cap.id_offset = offset;
+		offset += cap.packet_count;
+	}
+	caps
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn ours(protocol: &[u8; 3], version: u8, packet_count: u8) -> CapabilityInfo {
+		CapabilityInfo { protocol: *protocol, version, packet_count }
+	}
+
+	fn theirs(protocol: &[u8; 3], version: u8) -> PeerCapabilityInfo {
+		PeerCapabilityInfo { protocol: *protocol, version }
+	}
+
+	#[test]
+	fn selects_highest_common_version() {
+		let caps = negotiate_capabilities(
+			&[ours(b"eth", 62, 8), ours(b"eth", 63, 17), ours(b"par", 1, 21), ours(b"par", 2, 21)],
+			&[theirs(b"eth", 62), theirs(b"eth", 63), theirs(b"eth", 64), theirs(b"par", 1), theirs(b"les", 1)],
+		);
+
+		assert_eq!(caps.len(), 2);
+		assert_eq!((caps[0].protocol, caps[0].version), (*b"eth", 63));
+		assert_eq!((caps[1].protocol, caps[1].version), (*b"par", 1));
+		assert_eq!(caps[0].id_offset, PACKET_USER);
+		assert_eq!(caps[1].id_offset, PACKET_USER + 17);
+	}
+
+	#[test]
+	fn no_common_versions() {
+		let caps = negotiate_capabilities(&[ours(b"eth", 63, 17)], &[theirs(b"eth", 62), theirs(b"les", 1)]);
+		assert!(caps.is_empty());
+	}
+}
@@ -16,15 +16,16 @@
 
 use std::sync::{
 	Arc,
-	atomic::{AtomicBool, Ordering as AtomicOrdering}
+	atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering}
 };
+use std::net::{SocketAddr, TcpListener};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use parity_bytes::Bytes;
 use parking_lot::Mutex;
 
-use network::{PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration};
+use network::{PeerId, NetworkContext, NetworkProtocolHandler, NetworkConfiguration, NetworkIoMessage, DisconnectReason};
 use ethcore_network_devp2p::NetworkService;
 use parity_crypto::publickey::{Generator, Random};
 use ethcore_io::TimerToken;
@@ -111,6 +112,44 @@ fn net_start_stop() {
 	service.start().unwrap();
 }
 
+#[test]
+fn net_stop_releases_listener() {
+	let service = NetworkService::new(NetworkConfiguration::new_local(), None).unwrap();
+	service.start().unwrap();
+	let url = service.local_url().unwrap();
+	let address: SocketAddr = url.split('@').nth(1).unwrap().split('+').next().unwrap().parse().unwrap();
+	service.stop();
+	assert!(TcpListener::bind(address).is_ok());
+}
+
+#[test]
+fn net_shutdown_disconnects_peers() {
+	let key1 = Random.generate().unwrap();
+	let mut config1 = NetworkConfiguration::new_local();
+	config1.use_secret = Some(key1.secret().clone());
+	config1.boot_nodes = vec![ ];
+	let mut service1 = NetworkService::new(config1, None).unwrap();
+	service1.start().unwrap();
+	let handler1 = TestProtocol::register(&mut service1, false);
+	let mut config2 = NetworkConfiguration::new_local();
+	config2.boot_nodes = vec![ service1.local_url().unwrap() ];
+	let mut service2 = NetworkService::new(config2, None).unwrap();
+	service2.start().unwrap();
+	let handler2 = TestProtocol::register(&mut service2, false);
+
+	let deadline = Instant::now() + Duration::from_secs(30);
+	while !(handler1.got_packet() && handler2.got_packet()) {
+		assert!(Instant::now() < deadline, "peers did not connect");
+		thread::sleep(Duration::from_millis(50));
+	}
+
+	service1.io().send_message(NetworkIoMessage::Shutdown).unwrap();
+	while !handler2.got_disconnect() {
+		assert!(Instant::now() < deadline, "peer was not disconnected on shutdown");
+		thread::sleep(Duration::from_millis(50));
+	}
+}
+
 #[test]
 fn net_disconnect() {
 	let key1 = Random.generate().unwrap();
@@ -142,3 +181,118 @@ fn net_timeout() {
 		thread::sleep(Duration::from_millis(50));
 	}
 }
+
+struct BroadcastProtocol {
+	peers: AtomicUsize,
+	wait_for: usize,
+}
+
+impl NetworkProtocolHandler for BroadcastProtocol {
+	fn read(&self, _io: &dyn NetworkContext, _peer: &PeerId, _packet_id: u8, _data: &[u8]) {}
+
+	fn connected(&self, io: &dyn NetworkContext, _peer: &PeerId) {
+		if self.peers.fetch_add(1, AtomicOrdering::SeqCst) + 1 == self.wait_for {
+			io.broadcast(33, b"hello");
+		}
+	}
+
+	fn disconnected(&self, _io: &dyn NetworkContext, _peer: &PeerId) {}
+}
+
+#[test]
+fn net_broadcast() {
+	let key1 = Random.generate().unwrap();
+	let mut config1 = NetworkConfiguration::new_local();
+	config1.use_secret = Some(key1.secret().clone());
+	config1.boot_nodes = vec![ ];
+	config1.discovery_enabled = false;
+	let service1 = NetworkService::new(config1, None).unwrap();
+	service1.start().unwrap();
+	service1.register_protocol(
+		Arc::new(BroadcastProtocol { peers: AtomicUsize::new(0), wait_for: 2 }),
+		*b"tst",
+		&[(42u8, 1u8), (43u8, 1u8)],
+	).unwrap();
+
+	let mut handlers = Vec::new();
+	let mut services = Vec::new();
+	for _ in 0..2 {
+		let mut config = NetworkConfiguration::new_local();
+		config.boot_nodes = vec![ service1.local_url().unwrap() ];
+		// keep the peers from finding each other
+		config.discovery_enabled = false;
+		let mut service = NetworkService::new(config, None).unwrap();
+		service.start().unwrap();
+		handlers.push(TestProtocol::register(&mut service, false));
+		services.push(service);
+	}
+
+	let deadline = Instant::now() + Duration::from_secs(30);
+	while !handlers.iter().all(|h| h.got_packet()) {
+		assert!(Instant::now() < deadline, "broadcast did not reach every peer");
+		thread::sleep(Duration::from_millis(50));
+	}
+}
+
+/// Disconnects any peer that sends it packet 34.
+struct StrictProtocol {
+	got_disconnect: AtomicBool,
+}
+
+impl NetworkProtocolHandler for StrictProtocol {
+	fn read(&self, io: &dyn NetworkContext, peer: &PeerId, packet_id: u8, _data: &[u8]) {
+		if packet_id == 34 {
+			io.disconnect_peer_with_reason(*peer, DisconnectReason::BadProtocol);
+		}
+	}
+
+	fn connected(&self, _io: &dyn NetworkContext, _peer: &PeerId) {}
+
+	fn disconnected(&self, _io: &dyn NetworkContext, _peer: &PeerId) {
+		self.got_disconnect.store(true, AtomicOrdering::Relaxed);
+	}
+}
+
+/// Sends packet 34 as soon as a peer connects.
+struct OffendingProtocol {
+	got_disconnect: AtomicBool,
+}
+
+impl NetworkProtocolHandler for OffendingProtocol {
+	fn read(&self, _io: &dyn NetworkContext, _peer: &PeerId, _packet_id: u8, _data: &[u8]) {}
+
+	fn connected(&self, io: &dyn NetworkContext, peer: &PeerId) {
+		io.send(*peer, 34, b"bad".to_vec()).unwrap();
+	}
+
+	fn disconnected(&self, _io: &dyn NetworkContext, _peer: &PeerId) {
+		self.got_disconnect.store(true, AtomicOrdering::Relaxed);
+	}
+}
+
+#[test]
+fn net_disconnect_from_read() {
+	let key1 = Random.generate().unwrap();
+	let mut config1 = NetworkConfiguration::new_local();
+	config1.use_secret = Some(key1.secret().clone());
+	config1.boot_nodes = vec![ ];
+	config1.discovery_enabled = false;
+	let service1 = NetworkService::new(config1, None).unwrap();
+	service1.start().unwrap();
+	let strict = Arc::new(StrictProtocol { got_disconnect: AtomicBool::new(false) });
+	service1.register_protocol(strict.clone(), *b"tst", &[(42u8, 35u8)]).unwrap();
+
+	let mut config2 = NetworkConfiguration::new_local();
+	config2.boot_nodes = vec![ service1.local_url().unwrap() ];
+	config2.discovery_enabled = false;
+	let service2 = NetworkService::new(config2, None).unwrap();
+	service2.start().unwrap();
+	let offending = Arc::new(OffendingProtocol { got_disconnect: AtomicBool::new(false) });
+	service2.register_protocol(offending.clone(), *b"tst", &[(42u8, 35u8)]).unwrap();
+
+	let deadline = Instant::now() + Duration::from_secs(30);
+	while !(strict.got_disconnect.load(AtomicOrdering::Relaxed) && offending.got_disconnect.load(AtomicOrdering::Relaxed)) {
+		assert!(Instant::now() < deadline, "peers were not disconnected after a malformed packet");
+		thread::sleep(Duration::from_millis(50));
+	}
+}
@@ -114,6 +114,21 @@ pub enum Error {
 	/// Invalid node id
 	#[display(fmt = "Invalid node id")]
 	InvalidNodeId,
+	/// Outbound TCP connection could not be established
+	#[display(fmt = "Cannot connect to node: {}", _0)]
+	ConnectFailed(io::Error),
+	/// No free session slot left
+	#[display(fmt = "Too many peers")]
+	TooManyPeers,
+	/// Already connected or connecting to the peer
+	#[display(fmt = "Duplicate peer")]
+	DuplicatePeer,
+	/// The remote node id is our own
+	#[display(fmt = "Attempted to connect to self")]
+	SelfConnect,
+	/// The encryption handshake could not be started
+	#[display(fmt = "Handshake failed: {}", _0)]
+	HandshakeFailed(Box<Error>),
 	/// Packet size is over the protocol limit
 	#[display(fmt = "Packet is too large")]
 	OversizedPacket,
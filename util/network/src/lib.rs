@@ -93,10 +93,14 @@ pub enum NetworkIoMessage {
 	InitPublicInterface,
 	/// Disconnect a peer.
 	Disconnect(PeerId),
+	/// Disconnect a peer with the given reason.
+	DisconnectWithReason(PeerId, DisconnectReason),
 	/// Disconnect and temporary disable peer.
 	DisablePeer(PeerId),
 	/// Network has been started with the host as the given enode.
 	NetworkStarted(String),
+	/// Disconnect all peers and release the host's sockets.
+	Shutdown,
 }
 
 /// Shared session information
@@ -122,6 +126,15 @@ pub struct SessionInfo {
 	pub local_address: String,
 }
 
+/// Traffic counters of a session, including handshake and framing overhead.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+	/// Total bytes received from the peer
+	pub bytes_received: u64,
+	/// Total bytes sent to the peer
+	pub bytes_sent: u64,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct PeerCapabilityInfo {
 	pub protocol: ProtocolId,
@@ -212,6 +225,12 @@ pub struct NetworkConfiguration {
 	pub max_peers: u32,
 	/// Maximum handshakes
 	pub max_handshakes: u32,
+	/// Maximum number of inbound connections accepted per second. 0 means no limit
+	pub max_inbound_per_second: u32,
+	/// Maximum number of connections from a single remote IP address. 0 means no limit
+	pub max_connections_per_ip: u32,
+	/// Maximum number of bytes written to a single peer per second. 0 means no limit
+	pub max_outbound_bytes_per_second: u32,
 	/// Reserved protocols. Peers with <key> protocol get additional <value> connection slots.
 	pub reserved_protocols: HashMap<ProtocolId, u32>,
 	/// List of reserved node addresses.
@@ -247,6 +266,9 @@ impl NetworkConfiguration {
 			min_peers: 25,
 			max_peers: 50,
 			max_handshakes: 64,
+			max_inbound_per_second: 0,
+			max_connections_per_ip: 0,
+			max_outbound_bytes_per_second: 0,
 			reserved_protocols: HashMap::new(),
 			ip_filter: IpFilter::default(),
 			reserved_nodes: Vec::new(),
@@ -279,6 +301,10 @@ pub trait NetworkContext {
 	/// Send a packet over the network to another peer using specified protocol.
 	fn send_protocol(&self, protocol: ProtocolId, peer: PeerId, packet_id: PacketId, data: Vec<u8>) -> Result<(), Error>;
 
+	/// Send a packet to every connected peer using this object's subprotocol.
+	/// Peers which have not finished the handshake yet are skipped.
+	fn broadcast(&self, packet_id: PacketId, data: &[u8]);
+
 	/// Respond to a current network message. Panics if no there is no packet in the context. If the session is expired returns nothing.
 	fn respond(&self, packet_id: PacketId, data: Vec<u8>) -> Result<(), Error>;
 
@@ -288,6 +314,13 @@ pub trait NetworkContext {
 	/// Disconnect peer. Reconnect can be attempted later.
 	fn disconnect_peer(&self, peer: PeerId);
 
+	/// Disconnect peer, sending the given reason. Safe to call from within a handler callback;
+	/// the session is torn down after the callback returns. Implementations that can't send
+	/// a reason fall back to `disconnect_peer`.
+	fn disconnect_peer_with_reason(&self, peer: PeerId, _reason: DisconnectReason) {
+		self.disconnect_peer(peer)
+	}
+
 	/// Check if the session is still active.
 	fn is_expired(&self) -> bool;
 
@@ -300,6 +333,9 @@ pub trait NetworkContext {
 	/// Returns information on p2p session
 	fn session_info(&self, peer: PeerId) -> Option<SessionInfo>;
 
+	/// Returns traffic counters for the session of the given peer.
+	fn session_stats(&self, peer: PeerId) -> Option<SessionStats>;
+
 	/// Returns max version for a given protocol.
 	fn protocol_version(&self, protocol: ProtocolId, peer: PeerId) -> Option<u8>;
 
@@ -322,6 +358,10 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 		(**self).send_protocol(protocol, peer, packet_id, data)
 	}
 
+	fn broadcast(&self, packet_id: PacketId, data: &[u8]) {
+		(**self).broadcast(packet_id, data)
+	}
+
 	fn respond(&self, packet_id: PacketId, data: Vec<u8>) -> Result<(), Error> {
 		(**self).respond(packet_id, data)
 	}
@@ -334,6 +374,10 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 		(**self).disconnect_peer(peer)
 	}
 
+	fn disconnect_peer_with_reason(&self, peer: PeerId, reason: DisconnectReason) {
+		(**self).disconnect_peer_with_reason(peer, reason)
+	}
+
 	fn is_expired(&self) -> bool {
 		(**self).is_expired()
 	}
@@ -350,6 +394,10 @@ impl<'a, T> NetworkContext for &'a T where T: ?Sized + NetworkContext {
 		(**self).session_info(peer)
 	}
 
+	fn session_stats(&self, peer: PeerId) -> Option<SessionStats> {
+		(**self).session_stats(peer)
+	}
+
 	fn protocol_version(&self, protocol: ProtocolId, peer: PeerId) -> Option<u8> {
 		(**self).protocol_version(protocol, peer)
 	}